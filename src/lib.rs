@@ -0,0 +1,3613 @@
+//! Headless physics of 2d bodies tugged toward points, independent of any rendering
+
+use glam::Vec2 as VecXy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io,
+    ops::{Add, AddAssign, Mul},
+    path::Path,
+    time::Duration,
+};
+
+/// 2D vector in length-angle form
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct VecLa {
+    pub length: f32,
+    pub angle: f32,
+}
+
+/// generalization of {position, velocity, ...} of rotating 2d body
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct FieldScalars {
+    pub xy: VecXy,
+    pub angle: f32,
+}
+
+// /// Body statics for acceleration of some scalar
+// struct VelocityStatic {
+//     acc_scalar: f32,
+//     linear_friction_scalar: f32,
+//     constant_friction: f32,
+// }
+
+// /// VelocityStatic for {xy, angle}
+// struct VelocityStatics {
+//     xy: VelocityStatic,
+//     angle: VelocityStatic,
+// }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Tugger {
+    pub relative_body_handle_xy: VecLa,
+    pub world_dest: VecXy,
+    /// force per unit of distance between my handle and destination
+    #[serde(default = "Tugger::default_stiffness")]
+    pub stiffness: f32,
+    /// force per unit of my handle's velocity, opposing it
+    #[serde(default = "Tugger::default_damping")]
+    pub damping: f32,
+    /// when my uncapped force exceeds this, I break and am removed
+    pub break_force: Option<f32>,
+    /// when set, `world_dest` follows this handle of another body, which I tug back equally
+    pub to_body: Option<TugTarget>,
+    /// total work done so far by my force on my handle, and by its reaction on the target's
+    pub work: f32,
+}
+
+/// Identifier of a body, unaffected by adding or removing other bodies of its world
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct BodyHandle(u64);
+
+/// Handle of some body, e.g., of a scene's bodies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TugTarget {
+    pub body: BodyHandle,
+    pub handle: VecLa,
+}
+
+/// Outline of a body within its scale
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Rect,
+    /// inscribed in the body's scale, i.e., with diameter equal to its smallest extent
+    Circle,
+}
+
+/// Unit mesh a body is drawn with, scaled to its size
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeshKind {
+    Rect,
+    /// outline of `Rect`
+    RectWire,
+    Circle,
+    /// outline of `Circle`
+    CircleWire,
+}
+
+/// A 2d shape in the game world.
+/// Plain data without rendering resources, so clones are independent.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Body {
+    // statics: VelocityStatics,
+    /// unique among the bodies of my world, which assigns it
+    #[serde(default)]
+    pub id: BodyHandle,
+    pub acc_scalar: f32,
+    /// mass per unit of area
+    pub density: f32,
+    pub pos: FieldScalars,
+    pub vel: FieldScalars,
+    pub scale: VecXy,
+    pub shape: Shape,
+    /// my scale as a rectangle, kept while I'm toggled into a circle
+    #[serde(default)]
+    pub rect_scale: Option<VecXy>,
+    /// [red, green, blue, alpha], each in [0, 1]
+    #[serde(default = "Body::default_color")]
+    pub color: [f32; 4],
+    /// attached while dragging me with the mouse
+    pub mouse_tugger: Option<Tugger>,
+    pub tuggers: Vec<Tugger>,
+    /// when set, adding tuggers beyond this many replaces the oldest ones.
+    /// My mouse tugger doesn't count.
+    #[serde(default)]
+    pub max_tuggers: Option<usize>,
+    pub max_tug_handle_distance: f32,
+    /// when set, gravity does not affect me
+    pub ignore_gravity: bool,
+    /// when set, I have infinite mass: nothing moves me, and I'm not integrated
+    #[serde(default)]
+    pub static_body: bool,
+    /// total acceleration applied in my latest step
+    pub acc: FieldScalars,
+    /// overrides the number of integration substeps per update for me alone
+    pub substeps: Option<usize>,
+    /// fraction of approaching speed retained when bouncing off another body
+    pub restitution: f32,
+    /// deceleration per squared unit of speed, linear and angular alike, giving a terminal speed
+    #[serde(default)]
+    pub quadratic_drag: f32,
+    /// unbounded when unset
+    pub max_linear_speed: Option<f32>,
+    pub max_angular_speed: Option<f32>,
+    /// my most recent positions, oldest first, for drawing a motion trail
+    #[serde(skip)]
+    pub trail: VecDeque<VecXy>,
+    /// when set, I've been at rest for long enough that I'm no longer integrated
+    #[serde(default)]
+    pub asleep: bool,
+    /// number of consecutive ticks I've been at rest
+    #[serde(default)]
+    pub rest_ticks: usize,
+    /// how far I'm drawn faded for sleeping, from 0 while awake to 1 once asleep for a while
+    #[serde(skip)]
+    pub sleep_fade: f32,
+}
+
+/// Overlap of two bodies
+#[derive(Debug, Clone)]
+pub struct Collision {
+    /// shortest world translation of the second body that separates it from the first
+    pub mtv: VecXy,
+    /// unit direction of `mtv`, pointing away from the first body
+    pub normal: VecXy,
+    /// world point where the bodies touch, midway through the overlap along `normal`
+    pub point: VecXy,
+}
+
+/// Notable occurrence during a tick
+#[derive(Debug, Clone)]
+pub enum PhysicsEvent {
+    /// bodies `a` and `b`, by index, overlapped and were pushed apart.
+    /// `normal` points from `a` toward `b`, and `point` is where they touched.
+    Collision { a: usize, b: usize, normal: VecXy, point: VecXy },
+}
+
+/// Indices of bodies bucketed by the cells of a uniform grid that their bounding boxes overlap,
+/// so that only bodies sharing a cell need to be tested for collision
+pub struct SpatialGrid {
+    cells: HashMap<[i32; 2], Vec<usize>>,
+}
+
+/// Seeded xorshift64* generator, so randomized commands are reproducible
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rng(u64);
+
+/// Environmental acceleration acting on every body, e.g., gravity or wind
+pub trait ForceField {
+    fn accel_at(&self, body: &Body) -> FieldScalars;
+}
+
+/// Uniform acceleration of every body not ignoring gravity
+pub struct Gravity {
+    pub acc: VecXy,
+}
+
+/// Attraction toward a point, inversely proportional to the squared distance from it
+pub struct PointGravity {
+    pub center: VecXy,
+    /// acceleration at unit distance. Negative strength repels.
+    pub strength: f32,
+    /// distances are clamped to at least this, bounding the acceleration near `center`
+    pub min_distance: f32,
+}
+
+/// Uniform push in a constant direction
+pub struct Wind {
+    /// acceleration of every body, or with `by_cross_section`, of a square of `REFERENCE_SIZE`
+    /// and default density
+    pub acc: VecXy,
+    /// when set, bodies are pushed in proportion to their extent across the wind, over their mass
+    pub by_cross_section: bool,
+}
+
+/// Axis-aligned box slowing the bodies whose centers are within it, like a liquid
+pub struct ViscousRegion {
+    pub min: VecXy,
+    pub max: VecXy,
+    /// deceleration per unit of speed, linear and angular alike.
+    /// Below 1 / dt, bodies are never reversed within a step of dt ticks.
+    pub viscosity: f32,
+}
+
+/// Scheme advancing positions and velocities by accelerations
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Integrator {
+    /// moves by the old velocity, then accelerates
+    ExplicitEuler,
+    /// accelerates, then moves by the new velocity
+    #[default]
+    SemiImplicitEuler,
+    /// velocity Verlet: accelerates by the average of the accelerations before and after moving
+    Verlet,
+}
+
+/// Placement of the world view on screen
+#[derive(Debug, Copy, Clone)]
+pub struct Camera {
+    /// world translation of the view's center from the screen's center
+    pub offset: VecXy,
+    /// screen units per world unit
+    pub zoom: f32,
+    /// when set, world coordinates follow the math convention of y pointing up, and are flipped
+    /// on screen
+    pub y_up: bool,
+}
+
+/// Real time elapsed between frames, simulated in ticks of a fixed duration
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    /// real seconds simulated by each tick
+    pub tick_seconds: f32,
+    /// upper bound on the ticks due per frame. Time beyond them is dropped, e.g., after a
+    /// stall, rather than falling ever further behind.
+    pub max_ticks_per_frame: usize,
+    /// real seconds elapsed but not yet simulated, in `[0, tick_seconds)` between frames
+    pub accumulator: f32,
+}
+
+/// Commands triggered by key presses
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ToggleDrawMode,
+    CycleBoundaryMode,
+    Shake,
+    ToggleSwapAll,
+    ToggleIgnoreGravity,
+    RotateCcw,
+    RotateCw,
+    ToggleJitter,
+    ToggleRopeColors,
+    ToggleWireframe,
+    ToggleDebugDraw,
+    TogglePause,
+    Rewind,
+    SwapDests,
+    SnapToFloor,
+    ToggleStepTimings,
+    MirrorScene,
+    ToggleAdaptiveTimestep,
+    ToggleAutoPause,
+    RandomizeConstants,
+    ToggleLabels,
+    ToggleBounds,
+    ToggleGravity,
+    SaveScene,
+    LoadScene,
+    Reset,
+    SpawnBody,
+    DeleteBody,
+    ToggleSpeedHeatmap,
+    SlowDown,
+    SpeedUp,
+    StepOnce,
+    ToggleTrails,
+    ToggleWind,
+    ToggleStatic,
+    ResetCamera,
+    CycleIntegrator,
+    ToggleViscousPool,
+    FlipYAxis,
+    PinTugger,
+    ToggleShape,
+    SaveRepro,
+    LoadRepro,
+}
+
+/// Key triggering each `Action`, of any type of key
+#[derive(Debug, Clone)]
+pub struct KeyBindings<K> {
+    pub quit: K,
+    pub toggle_draw_mode: K,
+    pub cycle_boundary_mode: K,
+    pub shake: K,
+    pub toggle_swap_all: K,
+    pub toggle_ignore_gravity: K,
+    pub rotate_ccw: K,
+    pub rotate_cw: K,
+    pub toggle_jitter: K,
+    pub toggle_rope_colors: K,
+    pub toggle_wireframe: K,
+    pub toggle_debug_draw: K,
+    pub toggle_pause: K,
+    pub rewind: K,
+    pub swap_dests: K,
+    pub snap_to_floor: K,
+    pub toggle_step_timings: K,
+    pub mirror_scene: K,
+    pub toggle_adaptive_timestep: K,
+    pub toggle_auto_pause: K,
+    pub randomize_constants: K,
+    pub toggle_labels: K,
+    pub toggle_bounds: K,
+    pub toggle_gravity: K,
+    pub save_scene: K,
+    pub load_scene: K,
+    pub reset: K,
+    pub spawn_body: K,
+    pub delete_body: K,
+    pub toggle_speed_heatmap: K,
+    pub slow_down: K,
+    pub speed_up: K,
+    pub step_once: K,
+    pub toggle_trails: K,
+    pub toggle_wind: K,
+    pub toggle_static: K,
+    pub reset_camera: K,
+    pub cycle_integrator: K,
+    pub toggle_viscous_pool: K,
+    pub flip_y_axis: K,
+    pub pin_tugger: K,
+    pub toggle_shape: K,
+    pub save_repro: K,
+    pub load_repro: K,
+}
+
+/// Pauses a simulation once all its bodies stay at rest, until resumed
+#[derive(Debug, Clone, Default)]
+pub struct AutoPause {
+    /// when unset, never pauses
+    pub enabled: bool,
+    /// number of consecutive ticks after which all bodies were at rest
+    pub ticks_at_rest: usize,
+    /// set while paused, until resumed
+    pub paused: bool,
+}
+
+/// Durations of recent physics updates, summarized once per window
+#[derive(Debug, Default)]
+pub struct StepTimings {
+    samples: Vec<Duration>,
+}
+
+/// How bodies interact with the bounds
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum BoundaryMode {
+    /// bodies bounce off the bounds by their restitution
+    Walls,
+    /// bodies are stopped at the bounds
+    Clamp,
+    /// bodies leaving the bounds reappear at the opposite side
+    Wrap,
+    #[default]
+    None,
+}
+
+/// Settings of how a world advances its bodies, apart from its force fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsConfig {
+    pub gravity: VecXy,
+    pub boundary_mode: BoundaryMode,
+    /// [min, max] corners, which must be finite to be stored as JSON
+    pub bounds: [VecXy; 2],
+    pub integrator: Integrator,
+    pub substeps: usize,
+    pub adaptive_timestep: bool,
+    pub jitter: bool,
+    pub grid_cell_size: f32,
+    pub trail_length: usize,
+}
+
+/// Self-contained state of a world, from which it advances exactly as it would have,
+/// e.g., for reproducing a bug
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Repro {
+    pub config: PhysicsConfig,
+    /// state of the world's generator, seeded with `World::SEED`
+    pub rng: Rng,
+    pub ticks: u64,
+    pub bodies: Vec<Body>,
+}
+
+/// Plain data from which a game starts, stored as RON or JSON in scene files
+#[derive(Serialize, Deserialize)]
+pub struct Scene {
+    pub gravity: VecXy,
+    pub bodies: Vec<Body>,
+}
+
+/// User callback run at a fixed point of each tick
+pub type StepHook = Box<dyn FnMut(&mut World)>;
+
+/// Bodies along with everything advancing them tick by tick
+pub struct World {
+    pub bodies: Vec<Body>,
+    /// acceleration of every body not ignoring gravity, applied as a `Gravity` field
+    pub gravity: VecXy,
+    /// summed with gravity to find the environmental acceleration of each body
+    pub force_fields: Vec<Box<dyn ForceField>>,
+    pub boundary_mode: BoundaryMode,
+    /// [min, max] corners of the axis-aligned box the boundary mode applies to
+    pub bounds: [VecXy; 2],
+    pub integrator: Integrator,
+    /// number of integration steps per tick, each covering an equal fraction of it
+    pub substeps: usize,
+    /// when set, ticks are split into more substeps while bodies move fast
+    pub adaptive_timestep: bool,
+    /// when set, bodies receive tiny random accelerations, breaking symmetric deadlocks
+    pub jitter: bool,
+    /// side of the square cells bucketing bodies to find those that may collide
+    pub grid_cell_size: f32,
+    /// number of positions kept in each body's trail, none when zero
+    pub trail_length: usize,
+    pub rng: Rng,
+    /// number of ticks advanced, less those rewound
+    pub ticks: u64,
+    /// maximum number of snapshots kept in `history`, none when zero
+    pub history_len: usize,
+    /// snapshots of `bodies` before each of the most recent ticks, oldest first
+    pub history: VecDeque<Vec<Body>>,
+    /// what happened during the most recent tick
+    pub events: Vec<PhysicsEvent>,
+    /// invoked before each tick's integration
+    pre_step: Option<StepHook>,
+    /// invoked after each tick's integration
+    post_step: Option<StepHook>,
+    /// handle given to the next body added
+    next_id: u64,
+}
+
+/// Utility functions for `f32` type. Workaround of orphan rule.
+pub trait NegIf: Sized {
+    fn neg_if(self, cond: bool) -> Self;
+    fn toward_zero_saturating(self, by: f32) -> Self;
+    /// The equivalent angle in [-π, π)
+    fn wrapped_angle(self) -> Self;
+}
+
+/// Utility functions for `VecXy` type. Workaround of orphan rule.
+pub trait VecXyExt: Sized {
+    fn rotated(self, angle: f32) -> Self;
+    fn split_parr_perp(self, other: Self) -> [Self; 2];
+    fn with_length(self, length: f32) -> Self;
+    fn reduce_length_saturating(self, by: f32) -> Self;
+    fn length_capped(self, at: f32) -> Self;
+}
+
+/////////////////////////////////
+
+impl NegIf for f32 {
+    fn neg_if(self, cond: bool) -> Self {
+        if cond {
+            -self
+        } else {
+            self
+        }
+    }
+    fn toward_zero_saturating(self, by: f32) -> Self {
+        if self >= 0. {
+            (self - by).max(0.)
+        } else {
+            (self + by).min(0.)
+        }
+    }
+    fn wrapped_angle(self) -> Self {
+        use std::f32::consts::{PI, TAU};
+        (self + PI).rem_euclid(TAU) - PI
+    }
+}
+
+impl VecXyExt for VecXy {
+    fn rotated(self, angle: f32) -> Self {
+        let [sa, ca] = [angle.sin(), angle.cos()];
+        let [x, y] = self.to_array();
+        Self::new(x * ca - y * sa, x * sa + y * ca)
+    }
+    fn split_parr_perp(self, other: Self) -> [Self; 2] {
+        // projecting onto a zero vector yields zero rather than NaN
+        let parr = other * safe_div(self.dot(other), other.length_squared(), 0.);
+        let perp = self - parr;
+        [parr, perp]
+    }
+    fn with_length(self, length: f32) -> Self {
+        self.normalize_or_zero() * length
+    }
+    fn reduce_length_saturating(self, by: f32) -> Self {
+        self.with_length(self.length().toward_zero_saturating(by))
+    }
+    fn length_capped(self, cap: f32) -> Self {
+        let now = self.length();
+        if cap < now {
+            self * cap / now
+        } else {
+            self
+        }
+    }
+}
+
+/// `num / den`, or `fallback` where that quotient isn't finite, e.g. when `den` is zero
+pub fn safe_div(num: f32, den: f32, fallback: f32) -> f32 {
+    let quotient = num / den;
+    if quotient.is_finite() {
+        quotient
+    } else {
+        fallback
+    }
+}
+
+impl VecLa {
+    pub fn to_xy(self) -> VecXy {
+        VecXy::new(self.length, 0.).rotated(self.angle)
+    }
+    pub fn from_xy(xy: VecXy) -> Self {
+        Self { length: xy.length(), angle: xy.y.atan2(xy.x) }
+    }
+    /// Interpolates my length linearly toward `other`'s, and my angle along the shorter arc
+    /// toward `other`'s, e.g., across ±π rather than through 0
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            length: self.length + (other.length - self.length) * t,
+            angle: self.angle + (other.angle - self.angle).wrapped_angle() * t,
+        }
+    }
+    /// Offsets my angle
+    pub fn rotated(self, by: f32) -> Self {
+        Self { angle: self.angle + by, ..self }
+    }
+}
+/// Scales my length
+///
+/// ```
+/// # use torque_on_2d_shapes::VecLa;
+/// let scaled = VecLa { length: 2., angle: 0.5 } * 3.;
+/// assert_eq!((scaled.length, scaled.angle), (6., 0.5));
+/// ```
+impl Mul<f32> for VecLa {
+    type Output = Self;
+    fn mul(self, by: f32) -> Self {
+        Self { length: self.length * by, ..self }
+    }
+}
+/// Adds lengths and angles componentwise, i.e., not as vectors
+///
+/// ```
+/// # use torque_on_2d_shapes::VecLa;
+/// let sum = VecLa { length: 1., angle: 0.25 } + VecLa { length: 2., angle: 0.5 };
+/// assert_eq!((sum.length, sum.angle), (3., 0.75));
+/// ```
+impl Add for VecLa {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self { length: self.length + other.length, angle: self.angle + other.angle }
+    }
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift state must be nonzero
+        Self(seed.max(1))
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    /// uniformly distributed in [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        min + unit * (max - min)
+    }
+    /// random direction with a length uniformly distributed in [0, max_length)
+    pub fn vec_xy(&mut self, max_length: f32) -> VecXy {
+        let length = self.range(0., max_length);
+        let angle = self.range(-std::f32::consts::PI, std::f32::consts::PI);
+        VecLa { length, angle }.to_xy()
+    }
+}
+
+impl ForceField for Gravity {
+    fn accel_at(&self, body: &Body) -> FieldScalars {
+        let xy = if body.ignore_gravity { VecXy::ZERO } else { self.acc };
+        FieldScalars { xy, angle: 0. }
+    }
+}
+
+impl ForceField for PointGravity {
+    fn accel_at(&self, body: &Body) -> FieldScalars {
+        let toward = self.center - body.pos.xy;
+        let distance = toward.length().max(self.min_distance);
+        FieldScalars {
+            xy: toward.normalize_or_zero() * self.strength / (distance * distance),
+            angle: 0.,
+        }
+    }
+}
+
+impl Integrator {
+    pub fn next(self) -> Self {
+        match self {
+            Self::ExplicitEuler => Self::SemiImplicitEuler,
+            Self::SemiImplicitEuler => Self::Verlet,
+            Self::Verlet => Self::ExplicitEuler,
+        }
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self { offset: VecXy::ZERO, zoom: 1., y_up: false }
+    }
+}
+
+impl Camera {
+    /// Sign of the world y axis pointing down on screen
+    pub fn down(&self) -> f32 {
+        1f32.neg_if(self.y_up)
+    }
+    /// Flips of world axes on screen, and vice versa
+    fn flip(&self) -> VecXy {
+        VecXy::new(1., self.down())
+    }
+    /// World position shown at `screen_xy`, given the center of the screen
+    pub fn screen_to_world(&self, screen_center: VecXy, screen_xy: VecXy) -> VecXy {
+        screen_center + self.offset + (screen_xy - screen_center) * self.flip() / self.zoom
+    }
+    /// Point on screen showing `world_xy`, given the center of the screen
+    pub fn world_to_screen(&self, screen_center: VecXy, world_xy: VecXy) -> VecXy {
+        screen_center + (world_xy - screen_center - self.offset) * self.flip() * self.zoom
+    }
+    /// World length shown as `screen_length` long on screen
+    pub fn world_length(&self, screen_length: f32) -> f32 {
+        screen_length / self.zoom
+    }
+    /// Center and zoom the view on the world box from `min` to `max`, filling a screen of the
+    /// given center and size but for `margin` of it. Degenerate boxes keep the zoom.
+    pub fn fit(
+        &mut self,
+        screen_center: VecXy,
+        screen_size: VecXy,
+        [min, max]: [VecXy; 2],
+        margin: f32,
+    ) {
+        let zoom = (screen_size * (1. - margin) / (max - min)).min_element();
+        if zoom.is_finite() && zoom > 0. {
+            self.zoom = zoom;
+        }
+        // the screen's center shows its center plus my offset
+        self.offset = (min + max) * 0.5 - screen_center;
+    }
+    /// Move the view by the given screen translation
+    pub fn pan(&mut self, screen_delta: VecXy) {
+        self.offset += screen_delta * self.flip() / self.zoom;
+    }
+    /// Ease the view toward centering `target` on a screen of the given center, covering the
+    /// fraction `smoothing` of the remaining distance
+    pub fn follow(&mut self, target: VecXy, screen_center: VecXy, smoothing: f32) {
+        self.offset = self.offset.lerp(target - screen_center, smoothing);
+    }
+}
+
+impl FixedTimestep {
+    pub fn new(tick_seconds: f32, max_ticks_per_frame: usize) -> Self {
+        Self { tick_seconds, max_ticks_per_frame, accumulator: 0. }
+    }
+    /// Accumulate a frame of `frame_seconds`, returning the number of whole ticks now due
+    pub fn ticks_due(&mut self, frame_seconds: f32) -> usize {
+        self.accumulator += frame_seconds;
+        let mut ticks = 0;
+        while self.tick_seconds <= self.accumulator {
+            if ticks == self.max_ticks_per_frame {
+                // fell behind: drop the backlog rather than spiral
+                self.accumulator = 0.;
+                break;
+            }
+            self.accumulator -= self.tick_seconds;
+            ticks += 1;
+        }
+        ticks
+    }
+}
+
+impl AutoPause {
+    /// Number of consecutive ticks with all bodies at rest before pausing
+    pub const TICKS: usize = 120;
+
+    /// Count a tick after which all `bodies` are at rest and none is grabbed, pausing after
+    /// `TICKS` of them, or resume otherwise
+    pub fn update(&mut self, bodies: &[Body]) {
+        let resting = bodies.iter().all(|body| body.at_rest() && body.mouse_tugger.is_none());
+        if self.enabled && resting {
+            self.ticks_at_rest += 1;
+            self.paused = Self::TICKS <= self.ticks_at_rest;
+        } else {
+            self.resume();
+        }
+    }
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.ticks_at_rest = 0;
+    }
+}
+
+impl StepTimings {
+    /// Number of samples summarized together
+    pub const WINDOW: usize = 120;
+
+    /// [min, mean, max] of the recorded samples
+    pub fn stats(&self) -> Option<[Duration; 3]> {
+        let min = *self.samples.iter().min()?;
+        let max = *self.samples.iter().max()?;
+        let mean = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+        Some([min, mean, max])
+    }
+    /// Records a sample. Completing a window returns its stats and starts the next one.
+    pub fn record(&mut self, sample: Duration) -> Option<[Duration; 3]> {
+        self.samples.push(sample);
+        if self.samples.len() < Self::WINDOW {
+            return None;
+        }
+        let stats = self.stats();
+        self.samples.clear();
+        stats
+    }
+}
+
+impl<K: Copy + PartialEq> KeyBindings<K> {
+    pub fn bindings(&self) -> [(K, Action); 44] {
+        [
+            (self.quit, Action::Quit),
+            (self.toggle_draw_mode, Action::ToggleDrawMode),
+            (self.cycle_boundary_mode, Action::CycleBoundaryMode),
+            (self.shake, Action::Shake),
+            (self.toggle_swap_all, Action::ToggleSwapAll),
+            (self.toggle_ignore_gravity, Action::ToggleIgnoreGravity),
+            (self.rotate_ccw, Action::RotateCcw),
+            (self.rotate_cw, Action::RotateCw),
+            (self.toggle_jitter, Action::ToggleJitter),
+            (self.toggle_rope_colors, Action::ToggleRopeColors),
+            (self.toggle_wireframe, Action::ToggleWireframe),
+            (self.toggle_debug_draw, Action::ToggleDebugDraw),
+            (self.toggle_pause, Action::TogglePause),
+            (self.rewind, Action::Rewind),
+            (self.swap_dests, Action::SwapDests),
+            (self.snap_to_floor, Action::SnapToFloor),
+            (self.toggle_step_timings, Action::ToggleStepTimings),
+            (self.mirror_scene, Action::MirrorScene),
+            (self.toggle_adaptive_timestep, Action::ToggleAdaptiveTimestep),
+            (self.toggle_auto_pause, Action::ToggleAutoPause),
+            (self.randomize_constants, Action::RandomizeConstants),
+            (self.toggle_labels, Action::ToggleLabels),
+            (self.toggle_bounds, Action::ToggleBounds),
+            (self.toggle_gravity, Action::ToggleGravity),
+            (self.save_scene, Action::SaveScene),
+            (self.load_scene, Action::LoadScene),
+            (self.reset, Action::Reset),
+            (self.spawn_body, Action::SpawnBody),
+            (self.delete_body, Action::DeleteBody),
+            (self.toggle_speed_heatmap, Action::ToggleSpeedHeatmap),
+            (self.slow_down, Action::SlowDown),
+            (self.speed_up, Action::SpeedUp),
+            (self.step_once, Action::StepOnce),
+            (self.toggle_trails, Action::ToggleTrails),
+            (self.toggle_wind, Action::ToggleWind),
+            (self.toggle_static, Action::ToggleStatic),
+            (self.reset_camera, Action::ResetCamera),
+            (self.cycle_integrator, Action::CycleIntegrator),
+            (self.toggle_viscous_pool, Action::ToggleViscousPool),
+            (self.flip_y_axis, Action::FlipYAxis),
+            (self.pin_tugger, Action::PinTugger),
+            (self.toggle_shape, Action::ToggleShape),
+            (self.save_repro, Action::SaveRepro),
+            (self.load_repro, Action::LoadRepro),
+        ]
+    }
+    /// The action bound to the given key, if any. Earlier bindings take precedence.
+    pub fn action(&self, pressed: K) -> Option<Action> {
+        self.bindings().into_iter().find(|&(key, _)| key == pressed).map(|(_, action)| action)
+    }
+}
+
+impl BoundaryMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Walls => Self::Clamp,
+            Self::Clamp => Self::Wrap,
+            Self::Wrap => Self::None,
+            Self::None => Self::Walls,
+        }
+    }
+}
+
+impl Wind {
+    /// Side of the square body accelerated by exactly `acc` when pushing by cross section
+    pub const REFERENCE_SIZE: f32 = 50.;
+}
+
+impl ForceField for Wind {
+    fn accel_at(&self, body: &Body) -> FieldScalars {
+        let mut xy = self.acc;
+        if self.by_cross_section {
+            let across = self.acc.perp().normalize_or_zero();
+            let cross_section = (body.support(across) - body.support(-across)).dot(across);
+            let reference_mass = Body::DEFAULT_DENSITY * Self::REFERENCE_SIZE.powi(2);
+            xy *= safe_div(cross_section * reference_mass, body.mass() * Self::REFERENCE_SIZE, 0.);
+        }
+        FieldScalars { xy, angle: 0. }
+    }
+}
+
+impl ForceField for ViscousRegion {
+    fn accel_at(&self, body: &Body) -> FieldScalars {
+        if body.pos.xy.clamp(self.min, self.max) != body.pos.xy {
+            return FieldScalars::default();
+        }
+        body.vel.scaled(-self.viscosity)
+    }
+}
+
+impl SpatialGrid {
+    /// Bucket `bodies` into square cells with sides of `cell_size`.
+    /// A `cell_size` that isn't positive, e.g., NaN, buckets them all into one cell.
+    pub fn new(bodies: &[Body], cell_size: f32) -> Self {
+        let cell_size = if 0. < cell_size { cell_size } else { f32::INFINITY };
+        let mut cells: HashMap<[i32; 2], Vec<usize>> = HashMap::new();
+        for (index, body) in bodies.iter().enumerate() {
+            let half = body.aabb_half_extents();
+            let [min, max] = [body.pos.xy - half, body.pos.xy + half]
+                .map(|corner| (corner / cell_size).floor().to_array().map(|x| x as i32));
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    cells.entry([x, y]).or_default().push(index);
+                }
+            }
+        }
+        Self { cells }
+    }
+    /// Each pair `[a, b]` with `a < b` of bodies sharing a cell, once,
+    /// ordered by `b`, then by `a`
+    pub fn candidate_pairs(&self) -> Vec<[usize; 2]> {
+        let mut pairs: Vec<[usize; 2]> = self
+            .cells
+            .values()
+            .flat_map(|indices| {
+                indices.iter().enumerate().flat_map(move |(i, &a)| {
+                    indices[i + 1..].iter().map(move |&b| [a.min(b), a.max(b)])
+                })
+            })
+            .collect();
+        pairs.sort_unstable_by_key(|&[a, b]| (b, a));
+        pairs.dedup();
+        pairs
+    }
+}
+
+impl Tugger {
+    pub const DEFAULT_STIFFNESS: f32 = 0.01;
+    /// critically damps `DEFAULT_STIFFNESS`, i.e., 2√k
+    pub const DEFAULT_DAMPING: f32 = 0.2;
+    /// Rope colors cycled through by tugger index, as RGBA
+    pub const PALETTE: [[f32; 4]; 6] = [
+        [1., 0., 0., 1.],
+        [0., 1., 0., 1.],
+        [0., 0., 1., 1.],
+        [1., 1., 0., 1.],
+        [0., 1., 1., 1.],
+        [1., 0., 1., 1.],
+    ];
+    fn default_stiffness() -> f32 {
+        Self::DEFAULT_STIFFNESS
+    }
+    fn default_damping() -> f32 {
+        Self::DEFAULT_DAMPING
+    }
+    /// Color of the rope of the tugger at `index`, cycling through `PALETTE`
+    pub fn palette_color(index: usize) -> [f32; 4] {
+        Self::PALETTE[index % Self::PALETTE.len()]
+    }
+
+    /// Move my destination by the fraction `smoothing` of the way to `target`, low-pass
+    /// filtering it. A smoothing of 1 jumps right to `target`.
+    pub fn smooth_dest_toward(&mut self, target: VecXy, smoothing: f32) {
+        self.world_dest = self.world_dest.lerp(target, smoothing);
+    }
+}
+
+impl FieldScalars {
+    pub fn add_from(&mut self, other: &Self) {
+        *self += other.clone();
+    }
+    pub fn scaled(&self, by: f32) -> Self {
+        self.clone() * by
+    }
+    pub fn is_finite(&self) -> bool {
+        self.xy.is_finite() && self.angle.is_finite()
+    }
+}
+
+/// Adds linear and angular parts separately
+///
+/// ```
+/// # use glam::Vec2;
+/// # use torque_on_2d_shapes::FieldScalars;
+/// let a = FieldScalars { xy: Vec2::new(1., 2.), angle: 0.5 };
+/// let sum = a + FieldScalars { xy: Vec2::new(3., -1.), angle: 0.25 };
+/// assert_eq!((sum.xy, sum.angle), (Vec2::new(4., 1.), 0.75));
+/// ```
+impl Add for FieldScalars {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+/// ```
+/// # use glam::Vec2;
+/// # use torque_on_2d_shapes::FieldScalars;
+/// let mut acc = FieldScalars::default();
+/// acc += FieldScalars { xy: Vec2::new(1., 2.), angle: 0.5 };
+/// acc += FieldScalars { xy: Vec2::new(1., 2.), angle: 0.5 };
+/// assert_eq!((acc.xy, acc.angle), (Vec2::new(2., 4.), 1.));
+/// ```
+impl AddAssign for FieldScalars {
+    fn add_assign(&mut self, other: Self) {
+        self.xy += other.xy;
+        self.angle += other.angle;
+    }
+}
+/// Scales linear and angular parts alike
+///
+/// ```
+/// # use glam::Vec2;
+/// # use torque_on_2d_shapes::FieldScalars;
+/// let scaled = FieldScalars { xy: Vec2::new(1., -2.), angle: 0.5 } * 2.;
+/// assert_eq!((scaled.xy, scaled.angle), (Vec2::new(2., -4.), 1.));
+/// ```
+impl Mul<f32> for FieldScalars {
+    type Output = Self;
+    fn mul(self, by: f32) -> Self {
+        Self { xy: self.xy * by, angle: self.angle * by }
+    }
+}
+
+impl Body {
+    /// Minimum extent of bodies drawn or rescaled with the mouse
+    pub const MIN_DRAWN_SCALE: f32 = 4.;
+    /// Angle by which the selected body is rotated per key press
+    pub const ROTATE_STEP: f32 = std::f32::consts::PI / 36.;
+    /// Below this linear speed (and the angular equivalent) a body is considered at rest
+    pub const REST_SPEED_XY: f32 = 0.05;
+    pub const REST_SPEED_ANGLE: f32 = 0.001;
+    /// Number of consecutive ticks at rest after which a body falls asleep
+    pub const SLEEP_TICKS: usize = 60;
+    pub const DEFAULT_DENSITY: f32 = 0.001;
+    /// Opaque white
+    pub const DEFAULT_COLOR: [f32; 4] = [1.; 4];
+    /// Change of `sleep_fade` per frame
+    pub const SLEEP_FADE_STEP: f32 = 0.125;
+    /// Fraction of its diagonal by which a rectangle's corner may trail the farthest one in some
+    /// direction, and still be part of the edge touching whatever lies that way
+    const EDGE_TOLERANCE: f32 = 0.001;
+
+    fn default_color() -> [f32; 4] {
+        Self::DEFAULT_COLOR
+    }
+
+    /// New body at rest spanning the axis-aligned rectangle between the two given corners
+    pub fn new_spanning(a: VecXy, b: VecXy) -> Self {
+        let scale = (b - a).abs().max(VecXy::splat(Self::MIN_DRAWN_SCALE));
+        Body {
+            id: BodyHandle::default(),
+            acc_scalar: 0.2,
+            density: Self::DEFAULT_DENSITY,
+            pos: FieldScalars { xy: (a + b) * 0.5, angle: 0. },
+            vel: FieldScalars::default(),
+            scale,
+            shape: Shape::Rect,
+            rect_scale: None,
+            color: Self::DEFAULT_COLOR,
+            mouse_tugger: None,
+            tuggers: Vec::new(),
+            max_tuggers: None,
+            max_tug_handle_distance: scale.length() * 0.5,
+            ignore_gravity: false,
+            static_body: false,
+            acc: FieldScalars::default(),
+            substeps: None,
+            restitution: 0.5,
+            quadratic_drag: 0.,
+            max_linear_speed: None,
+            max_angular_speed: None,
+            trail: VecDeque::new(),
+            asleep: false,
+            rest_ticks: 0,
+            sleep_fade: 0.,
+        }
+    }
+
+    /// Half extents of my axis-aligned bounding box, accounting for rotation
+    pub fn aabb_half_extents(&self) -> VecXy {
+        if self.shape == Shape::Circle {
+            return VecXy::splat(self.radius());
+        }
+        let [sa, ca] = [self.pos.angle.sin().abs(), self.pos.angle.cos().abs()];
+        let [w, h] = self.scale.to_array();
+        VecXy::new(w * ca + h * sa, w * sa + h * ca) * 0.5
+    }
+
+    /// Radius of me as a circle
+    pub fn radius(&self) -> f32 {
+        self.scale.min_element() * 0.5
+    }
+    /// Mesh drawing me, filled or as a `wireframe`, along with its scale
+    pub fn mesh(&self, wireframe: bool) -> (MeshKind, VecXy) {
+        match (self.shape, wireframe) {
+            (Shape::Rect, false) => (MeshKind::Rect, self.scale),
+            (Shape::Rect, true) => (MeshKind::RectWire, self.scale),
+            (Shape::Circle, false) => (MeshKind::Circle, VecXy::splat(self.radius() * 2.)),
+            (Shape::Circle, true) => (MeshKind::CircleWire, VecXy::splat(self.radius() * 2.)),
+        }
+    }
+    /// Radius of the smallest circle about my center containing me, whatever my angle
+    pub fn bounding_radius(&self) -> f32 {
+        match self.shape {
+            Shape::Rect => self.scale.length() * 0.5,
+            Shape::Circle => self.radius(),
+        }
+    }
+
+    /// My corners in world space, as a rectangle
+    fn corners(&self) -> [VecXy; 4] {
+        let [w, h] = (self.scale * 0.5).to_array();
+        [VecXy::new(-w, -h), VecXy::new(w, -h), VecXy::new(w, h), VecXy::new(-w, h)]
+            .map(|corner| self.pos.xy + corner.rotated(self.pos.angle))
+    }
+
+    /// My world point farthest along `direction`
+    pub fn support(&self, direction: VecXy) -> VecXy {
+        match self.shape {
+            Shape::Rect => {
+                let corners = self.corners();
+                corners.into_iter().fold(corners[0], |farthest, corner| {
+                    if farthest.dot(direction) < corner.dot(direction) {
+                        corner
+                    } else {
+                        farthest
+                    }
+                })
+            }
+            Shape::Circle => self.pos.xy + direction.with_length(self.radius()),
+        }
+    }
+
+    /// My overlap with `other`, if any
+    pub fn collides_with(&self, other: &Body) -> Option<Collision> {
+        // cheaply rule out bodies whose bounding circles are apart
+        let reach = self.bounding_radius() + other.bounding_radius();
+        if reach * reach < self.pos.xy.distance_squared(other.pos.xy) {
+            return None;
+        }
+        self.shape_collision(other)
+    }
+    /// My overlap with `other` by our exact shapes, without ruling anything out first
+    fn shape_collision(&self, other: &Body) -> Option<Collision> {
+        match (self.shape, other.shape) {
+            (Shape::Rect, Shape::Rect) => self.rect_collision(other),
+            (Shape::Circle, Shape::Circle) => self.circle_collision(other),
+            (Shape::Rect, Shape::Circle) => self.rect_circle_collision(other),
+            (Shape::Circle, Shape::Rect) => {
+                other.rect_circle_collision(self).map(|Collision { mtv, normal, point }| {
+                    Collision { mtv: -mtv, normal: -normal, point }
+                })
+            }
+        }
+    }
+    /// Overlap of two circles
+    fn circle_collision(&self, other: &Body) -> Option<Collision> {
+        let offset = other.pos.xy - self.pos.xy;
+        let depth = self.radius() + other.radius() - offset.length();
+        if depth <= 0. {
+            return None;
+        }
+        // coincident centers are separated along x
+        let normal = offset.try_normalize().unwrap_or(VecXy::X);
+        let point = self.pos.xy + normal * (self.radius() - depth * 0.5);
+        Some(Collision { mtv: normal * depth, normal, point })
+    }
+    /// Overlap of my rectangle with the given circle, found in my local frame
+    fn rect_circle_collision(&self, circle: &Body) -> Option<Collision> {
+        let half = self.scale * 0.5;
+        let radius = circle.radius();
+        let local = (circle.pos.xy - self.pos.xy).rotated(-self.pos.angle);
+        let closest = local.clamp(-half, half);
+        let (local_normal, depth) = if closest != local {
+            let offset = local - closest;
+            let distance = offset.length();
+            if distance >= radius {
+                return None;
+            }
+            (offset / distance, radius - distance)
+        } else {
+            // center within me: push it out through my nearest edge
+            let to_edge = half - local.abs();
+            if to_edge.x < to_edge.y {
+                (VecXy::new(local.x.signum(), 0.), to_edge.x + radius)
+            } else {
+                (VecXy::new(0., local.y.signum()), to_edge.y + radius)
+            }
+        };
+        let normal = local_normal.rotated(self.pos.angle);
+        let point = circle.pos.xy - normal * (radius - depth * 0.5);
+        Some(Collision { mtv: normal * depth, normal, point })
+    }
+    /// Overlap of two rectangles, found with the separating axis theorem on their edges
+    fn rect_collision(&self, other: &Body) -> Option<Collision> {
+        let [mine, theirs] = [self.corners(), other.corners()];
+        let project = |corners: &[VecXy; 4], axis: VecXy| {
+            corners
+                .iter()
+                .map(|corner| corner.dot(axis))
+                .fold([f32::INFINITY, f32::NEG_INFINITY], |[min, max], along| {
+                    [min.min(along), max.max(along)]
+                })
+        };
+        let mut best: Option<(f32, VecXy)> = None;
+        for angle in [self.pos.angle, other.pos.angle] {
+            let edge = VecXy::X.rotated(angle);
+            for axis in [edge, edge.perp()] {
+                let [[my_min, my_max], [their_min, their_max]] =
+                    [project(&mine, axis), project(&theirs, axis)];
+                // distances `other` must be pushed along +axis and -axis respectively
+                let [forward, backward] = [my_max - their_min, their_max - my_min];
+                let (depth, normal) =
+                    if forward < backward { (forward, axis) } else { (backward, -axis) };
+                if depth <= 0. {
+                    // found a separating axis
+                    return None;
+                }
+                if best.is_none_or(|(best_depth, _)| depth < best_depth) {
+                    best = Some((depth, normal));
+                }
+            }
+        }
+        let (depth, normal) = best?;
+        // the touching features, i.e., a corner or an edge of each, clipped against each other
+        // across the normal. Midway through the overlap along it.
+        let tangent = normal.perp();
+        let [[my_min, my_max], [their_min, their_max]] = [
+            Self::feature_span(&mine, normal, tangent),
+            Self::feature_span(&theirs, -normal, tangent),
+        ];
+        let across = (my_min.max(their_min) + my_max.min(their_max)) * 0.5;
+        let along = mine.iter().map(|corner| corner.dot(normal)).fold(f32::NEG_INFINITY, f32::max)
+            - depth * 0.5;
+        Some(Collision { mtv: normal * depth, normal, point: normal * along + tangent * across })
+    }
+    /// [min, max] along `tangent` of the corners farthest along `direction`, i.e., of the corner
+    /// or edge touching whatever lies that way
+    fn feature_span(corners: &[VecXy; 4], direction: VecXy, tangent: VecXy) -> [f32; 2] {
+        let farthest =
+            corners.iter().map(|corner| corner.dot(direction)).fold(f32::NEG_INFINITY, f32::max);
+        // nearly parallel edges still touch along their length
+        let extent = (corners[2] - corners[0]).length();
+        corners
+            .iter()
+            .filter(|corner| farthest - corner.dot(direction) <= extent * Self::EDGE_TOLERANCE)
+            .map(|corner| corner.dot(tangent))
+            .fold([f32::INFINITY, f32::NEG_INFINITY], |[min, max], along| {
+                [min.min(along), max.max(along)]
+            })
+    }
+
+    /// Push `other` and me apart along `collision` from `self.collides_with(other)`, and
+    /// exchange an impulse at the contact point if we are approaching.
+    /// The lighter body moves more, and static bodies not at all.
+    pub fn resolve_collision(&mut self, other: &mut Body, collision: &Collision) {
+        if self.static_body && other.static_body {
+            return;
+        }
+        let [my_inv_mass, their_inv_mass] = [self.inv_mass(), other.inv_mass()];
+        let [my_share, their_share] = {
+            let total = my_inv_mass + their_inv_mass;
+            [safe_div(my_inv_mass, total, 0.5), safe_div(their_inv_mass, total, 0.5)]
+        };
+        self.pos.xy -= collision.mtv * my_share;
+        other.pos.xy += collision.mtv * their_share;
+
+        let n = collision.normal;
+        let contact = collision.point;
+        let [my_r, their_r] = [contact - self.pos.xy, contact - other.pos.xy];
+
+        let my_vel = self.vel.xy + my_r.perp() * self.vel.angle;
+        let their_vel = other.vel.xy + their_r.perp() * other.vel.angle;
+        let approach = (their_vel - my_vel).dot(n);
+        if approach >= 0. {
+            // already separating
+            return;
+        }
+        let restitution = self.restitution.max(other.restitution);
+        let resistance = my_inv_mass
+            + their_inv_mass
+            + my_r.perp_dot(n).powi(2) * self.inv_inertia()
+            + their_r.perp_dot(n).powi(2) * other.inv_inertia();
+        let impulse = n * safe_div(-(1. + restitution) * approach, resistance, 0.);
+
+        self.vel += self.impulse_response(my_r, -impulse);
+        other.vel += other.impulse_response(their_r, impulse);
+    }
+
+    /// Clamp my position such that my bounding box lies within the axis-aligned box from
+    /// `min` to `max`, zeroing any velocity component pushing me further out
+    pub fn clamp_within(&mut self, min: VecXy, max: VecXy) {
+        self.keep_within(min, max, 0.);
+    }
+    /// Like `clamp_within`, but bouncing any velocity component pushing me further out
+    /// by my restitution instead
+    pub fn bounce_within(&mut self, min: VecXy, max: VecXy) {
+        self.keep_within(min, max, self.restitution);
+    }
+    fn keep_within(&mut self, min: VecXy, max: VecXy, restitution: f32) {
+        let half = self.aabb_half_extents();
+        let [min, max] = [min + half, max - half];
+        for i in 0..2 {
+            if max[i] < min[i] {
+                // too large to fit: center me instead
+                self.pos.xy[i] = (min[i] + max[i]) * 0.5;
+                self.vel.xy[i] = 0.;
+            } else if self.pos.xy[i] < min[i] {
+                self.pos.xy[i] = min[i];
+                if self.vel.xy[i] < 0. {
+                    self.vel.xy[i] *= -restitution;
+                }
+            } else if max[i] < self.pos.xy[i] {
+                self.pos.xy[i] = max[i];
+                if self.vel.xy[i] > 0. {
+                    self.vel.xy[i] *= -restitution;
+                }
+            }
+        }
+    }
+
+    /// Turn me from a rectangle into the circle bounding it, or back, keeping my motion and
+    /// tuggers. My max tug handle distance becomes my new bounding radius.
+    pub fn toggle_shape(&mut self) {
+        match self.shape {
+            Shape::Rect => {
+                self.rect_scale = Some(self.scale);
+                self.scale = VecXy::splat(self.bounding_radius() * 2.);
+                self.shape = Shape::Circle;
+            }
+            Shape::Circle => {
+                // circles never toggled from a rectangle become the square they bound
+                let side = self.radius() * std::f32::consts::SQRT_2;
+                self.scale = self.rect_scale.take().unwrap_or(VecXy::splat(side));
+                self.shape = Shape::Rect;
+            }
+        }
+        self.max_tug_handle_distance = self.bounding_radius();
+    }
+
+    /// Scale me uniformly by `factor` about my center, along with my reach and tugger handles.
+    /// Shrinking stops once my smallest extent reaches `MIN_DRAWN_SCALE`.
+    pub fn rescale(&mut self, factor: f32) {
+        let factor = factor.max(safe_div(Self::MIN_DRAWN_SCALE, self.scale.min_element(), 1.));
+        self.scale *= factor;
+        if let Some(rect_scale) = &mut self.rect_scale {
+            *rect_scale *= factor;
+        }
+        self.max_tug_handle_distance *= factor;
+        for tugger in self.all_tuggers_mut() {
+            tugger.relative_body_handle_xy.length *= factor;
+        }
+    }
+
+    /// Move my center back into the axis-aligned box from `min` to `max` through the opposite
+    /// side, keeping my velocity. My tuggers' destinations stay put.
+    pub fn wrap_within(&mut self, min: VecXy, max: VecXy) {
+        let size = max - min;
+        for i in 0..2 {
+            if size[i] > 0. {
+                self.pos.xy[i] = min[i] + (self.pos.xy[i] - min[i]).rem_euclid(size[i]);
+            }
+        }
+    }
+
+    pub fn mass(&self) -> f32 {
+        let area = match self.shape {
+            Shape::Rect => self.scale.x * self.scale.y,
+            Shape::Circle => std::f32::consts::PI * self.radius().powi(2),
+        };
+        area * self.density
+    }
+    /// Moment of inertia about my center of mass
+    pub fn inertia(&self) -> f32 {
+        match self.shape {
+            Shape::Rect => self.mass() * self.scale.length_squared() / 12.,
+            Shape::Circle => self.mass() * self.radius().powi(2) * 0.5,
+        }
+    }
+    /// Zero if I'm static, i.e., of infinite mass
+    pub fn inv_mass(&self) -> f32 {
+        if self.static_body {
+            0.
+        } else {
+            safe_div(1., self.mass(), 0.)
+        }
+    }
+    /// Zero if I'm static, i.e., of infinite inertia
+    pub fn inv_inertia(&self) -> f32 {
+        if self.static_body {
+            0.
+        } else {
+            safe_div(1., self.inertia(), 0.)
+        }
+    }
+    /// Change of my velocity by the given impulse at `contact`, relative to my center of mass
+    pub fn impulse_response(&self, contact: VecXy, impulse: VecXy) -> FieldScalars {
+        FieldScalars {
+            xy: impulse * self.inv_mass(),
+            angle: contact.perp_dot(impulse) * self.inv_inertia(),
+        }
+    }
+    pub fn momentum(&self) -> VecXy {
+        self.vel.xy * self.mass()
+    }
+    /// Linear plus rotational kinetic energy
+    pub fn kinetic_energy(&self) -> f32 {
+        0.5 * self.mass() * self.vel.xy.length_squared()
+            + 0.5 * self.inertia() * self.vel.angle.powi(2)
+    }
+
+    /// My mouse tugger, if any, followed by my other tuggers
+    pub fn all_tuggers(&self) -> impl Iterator<Item = &Tugger> + '_ {
+        self.mouse_tugger.iter().chain(self.tuggers.iter())
+    }
+    pub fn all_tuggers_mut(&mut self) -> impl Iterator<Item = &mut Tugger> + '_ {
+        self.mouse_tugger.iter_mut().chain(self.tuggers.iter_mut())
+    }
+
+    /// Add `tugger` to my tuggers, removing the oldest ones beyond `max_tuggers`
+    pub fn add_tugger(&mut self, tugger: Tugger) {
+        self.tuggers.push(tugger);
+        if let Some(max) = self.max_tuggers {
+            let excess = self.tuggers.len().saturating_sub(max);
+            self.tuggers.drain(..excess);
+        }
+    }
+
+    /// Human-readable descriptions of any misconfiguration
+    pub fn validate(&self) -> Vec<String> {
+        self.tuggers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tugger)| {
+                let length = tugger.relative_body_handle_xy.length;
+                if length > self.max_tug_handle_distance {
+                    Some(format!(
+                        "tugger {} handle length {} exceeds max tug handle distance {}",
+                        index, length, self.max_tug_handle_distance
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Mirror me across the vertical line at `center_x`, including my velocity and tuggers.
+    /// Handles are mirrored in my local frame, keeping them attached to the same material point.
+    pub fn mirror_x(&mut self, center_x: f32) {
+        self.pos.xy.x = 2. * center_x - self.pos.xy.x;
+        self.pos.angle = -self.pos.angle;
+        self.vel.xy.x = -self.vel.xy.x;
+        self.vel.angle = -self.vel.angle;
+        for tugger in self.all_tuggers_mut() {
+            tugger.world_dest.x = 2. * center_x - tugger.world_dest.x;
+            let handle = &mut tugger.relative_body_handle_xy;
+            handle.angle = std::f32::consts::PI - handle.angle;
+            // targets are assumed to be mirrored along with me
+            if let Some(TugTarget { handle, .. }) = &mut tugger.to_body {
+                handle.angle = std::f32::consts::PI - handle.angle;
+            }
+        }
+    }
+
+    /// Scale each of my constants by a random factor in [1 - spread, 1 + spread).
+    /// My max tug handle distance never shrinks below the reach of my existing tuggers.
+    pub fn perturb_constants(&mut self, rng: &mut Rng, spread: f32) {
+        self.acc_scalar *= rng.range(1. - spread, 1. + spread);
+        self.density *= rng.range(1. - spread, 1. + spread);
+        let min_reach = self
+            .all_tuggers()
+            .map(|tugger| tugger.relative_body_handle_xy.length)
+            .fold(0., f32::max);
+        self.max_tug_handle_distance =
+            (self.max_tug_handle_distance * rng.range(1. - spread, 1. + spread)).max(min_reach);
+    }
+
+    /// Rotate me in place by the given angle, stopping my spin
+    pub fn rotate_by(&mut self, by: f32) {
+        self.pos.angle += by;
+        self.vel.angle = 0.;
+    }
+
+    /// Move me vertically until my lowest point touches `floor`, stopping me.
+    /// `down` is the sign of the y axis pointing toward the floor.
+    pub fn snap_to_floor(&mut self, floor: f32, down: f32) {
+        self.pos.xy.y = floor - down * self.aabb_half_extents().y;
+        self.vel = FieldScalars::default();
+    }
+
+    /// Recover from any non-finite state, e.g. caused by a degenerate configuration, by stopping
+    /// me, and moving me to `fallback` if my position is affected. Returns whether I recovered.
+    pub fn recover_finite(&mut self, fallback: &FieldScalars) -> bool {
+        if self.pos.is_finite() && self.vel.is_finite() {
+            return false;
+        }
+        self.vel = FieldScalars::default();
+        self.acc = FieldScalars::default();
+        if !self.pos.is_finite() {
+            self.pos = fallback.clone();
+        }
+        true
+    }
+
+    /// Limit my velocity to my max speeds
+    pub fn clamp_speeds(&mut self) {
+        if let Some(max) = self.max_linear_speed {
+            self.vel.xy = self.vel.xy.length_capped(max);
+        }
+        if let Some(max) = self.max_angular_speed {
+            self.vel.angle = self.vel.angle.max(-max).min(max);
+        }
+    }
+
+    /// Instantly change my velocity as if `delta_vel` were applied at the world point `contact_xy`,
+    /// which also spins me unless it's in line with my center of mass.
+    /// Static bodies are unaffected.
+    pub fn apply_impulse(&mut self, contact_xy: VecXy, delta_vel: VecXy) {
+        self.vel += self.tug_acc(contact_xy - self.pos.xy, delta_vel);
+    }
+    /// Append my position to my trail, dropping the oldest beyond `max_len`
+    pub fn record_trail(&mut self, max_len: usize) {
+        while max_len <= self.trail.len() && !self.trail.is_empty() {
+            self.trail.pop_front();
+        }
+        if 0 < max_len {
+            self.trail.push_back(self.pos.xy);
+        }
+    }
+    pub fn at_rest(&self) -> bool {
+        self.vel.xy.length() < Self::REST_SPEED_XY && self.vel.angle.abs() < Self::REST_SPEED_ANGLE
+    }
+    /// Count another tick at rest, falling asleep after `SLEEP_TICKS` of them,
+    /// or wake me if I'm moving or grabbed, e.g., after a collision
+    pub fn update_sleep(&mut self) {
+        if !self.at_rest() || self.mouse_tugger.is_some() {
+            self.wake();
+            return;
+        }
+        self.rest_ticks += 1;
+        if !self.asleep && Self::SLEEP_TICKS <= self.rest_ticks {
+            self.asleep = true;
+            self.vel = FieldScalars::default();
+        }
+    }
+    /// Advance my sleep fade by a frame, toward faded while I'm asleep and back otherwise
+    pub fn update_sleep_fade(&mut self) {
+        let step = Self::SLEEP_FADE_STEP.neg_if(!self.asleep);
+        self.sleep_fade = (self.sleep_fade + step).clamp(0., 1.);
+    }
+    pub fn wake(&mut self) {
+        self.asleep = false;
+        self.rest_ticks = 0;
+    }
+    /// Whether the given world point lies within my shape
+    pub fn contains(&self, world_xy: VecXy) -> bool {
+        let local = (world_xy - self.pos.xy).rotated(-self.pos.angle);
+        match self.shape {
+            Shape::Rect => {
+                let half = self.scale * 0.5;
+                local.x.abs() <= half.x && local.y.abs() <= half.y
+            }
+            Shape::Circle => local.length() <= self.radius(),
+        }
+    }
+    /// My handle at the given world point, brought within my max tug handle distance
+    pub fn grab_handle(&self, world_xy: VecXy) -> VecLa {
+        let handle = VecLa::from_xy((world_xy - self.pos.xy).rotated(-self.pos.angle));
+        VecLa { length: handle.length.min(self.max_tug_handle_distance), ..handle }
+    }
+    /// [start, end] world angles of the arc sweeping from my orientation to the given handle
+    pub fn handle_arc_angles(&self, body_handle: VecLa) -> [f32; 2] {
+        [self.pos.angle, self.pos.angle + body_handle.angle]
+    }
+    pub fn xy_relative_handle(&self, mut body_handle: VecLa) -> VecXy {
+        body_handle.angle += self.pos.angle;
+        body_handle.to_xy()
+    }
+    pub fn absolute_handle(&self, body_handle: VecLa) -> VecXy {
+        self.xy_relative_handle(body_handle) + self.pos.xy
+    }
+
+    /// Integrate the effects of my tuggers and the given force fields over `dt` ticks
+    pub fn step(&mut self, dt: f32, fields: &[&dyn ForceField], integrator: Integrator) {
+        // break overstrained tuggers
+        if self.mouse_tugger.as_ref().is_some_and(|tugger| self.overstrains(tugger)) {
+            self.mouse_tugger = None;
+        }
+        let tuggers = std::mem::take(&mut self.tuggers);
+        self.tuggers = tuggers.into_iter().filter(|tugger| !self.overstrains(tugger)).collect();
+
+        let (acc, tugs) = self.acceleration(dt, fields);
+        let pos_before = self.pos.xy;
+        match integrator {
+            Integrator::ExplicitEuler => {
+                self.pos += self.vel.scaled(dt);
+                self.vel += acc.scaled(dt);
+            }
+            Integrator::SemiImplicitEuler => {
+                self.vel += acc.scaled(dt);
+                self.pos += self.vel.scaled(dt);
+            }
+            Integrator::Verlet => {
+                // velocity-dependent forces are evaluated at the half step velocity
+                self.vel += acc.scaled(dt * 0.5);
+                self.pos += self.vel.scaled(dt);
+                let (acc_after, _) = self.acceleration(dt, fields);
+                self.vel += acc_after.scaled(dt * 0.5);
+            }
+        }
+        // unbounded angles lose precision
+        self.pos.angle = self.pos.angle.wrapped_angle();
+        self.acc = acc;
+
+        // accumulate work of each tugger's force along the displacement of its handle
+        let mass = self.mass();
+        let works: Vec<f32> = self
+            .all_tuggers()
+            .zip(tugs)
+            .map(|(tugger, [xy_relative_handle_before, force])| {
+                let handle_before = pos_before + xy_relative_handle_before;
+                // tug forces are given per unit of my mass
+                let displacement =
+                    self.absolute_handle(tugger.relative_body_handle_xy) - handle_before;
+                force.dot(displacement) * mass
+            })
+            .collect();
+        for (tugger, work) in self.all_tuggers_mut().zip(works) {
+            tugger.work += work;
+        }
+
+        // // linear friction
+        // self.vel.xy *= self.statics.xy.linear_friction_scalar;
+        // self.vel.angle *= self.statics.angle.linear_friction_scalar;
+
+        // // constant friction
+        // self.vel.xy = self.vel.xy.reduce_length_saturating(self.statics.xy.constant_friction);
+        // self.vel.angle =
+        //     self.vel.angle.toward_zero_saturating(self.statics.angle.constant_friction);
+    }
+
+    /// My acceleration in my current state, along with the tugs contributing to it
+    fn acceleration(&self, dt: f32, fields: &[&dyn ForceField]) -> (FieldScalars, Vec<[VecXy; 2]>) {
+        // [handle relative to center of mass, force] of each tugger
+        let tugs: Vec<[VecXy; 2]> = self
+            .all_tuggers()
+            .map(|tugger| {
+                let xy_relative_handle = self.xy_relative_handle(tugger.relative_body_handle_xy);
+
+                let force = self.tug_force(tugger).length_capped(self.acc_scalar);
+
+                // let force = tugger.world_dest - (xy_relative_handle + self.pos.xy);
+                [xy_relative_handle, force]
+            })
+            .collect();
+
+        // update velocity wrt tug
+        let mut acc = tugs
+            .iter()
+            .map(|&[xy_relative_handle, force]| self.tug_acc(xy_relative_handle, force))
+            .fold(FieldScalars::default(), |sum, acc| sum + acc);
+
+        // environmental forces
+        for field in fields {
+            acc.add_from(&field.accel_at(self));
+        }
+
+        // quadratic drag, which slows but never reverses me within a step
+        let speed = self.vel.xy.length();
+        acc.xy -= (self.vel.xy * speed * self.quadratic_drag).length_capped(speed / dt);
+        let spin = self.vel.angle.abs();
+        acc.angle -= (self.vel.angle * spin * self.quadratic_drag).clamp(-spin / dt, spin / dt);
+        (acc, tugs)
+    }
+
+    /// Spring-damper force of the given tugger on me, before capping by my acceleration scalar
+    fn tug_force(&self, tugger: &Tugger) -> VecXy {
+        let handle = self.absolute_handle(tugger.relative_body_handle_xy);
+        let handle_vel = self.velocity_at(tugger.relative_body_handle_xy);
+        (tugger.world_dest - handle) * tugger.stiffness - handle_vel * tugger.damping
+    }
+    fn overstrains(&self, tugger: &Tugger) -> bool {
+        tugger.break_force.is_some_and(|limit| limit < self.tug_force(tugger).length())
+    }
+
+    /// World velocity of the given handle, combining my linear and angular velocity
+    pub fn velocity_at(&self, body_handle: VecLa) -> VecXy {
+        self.vel.xy + self.xy_relative_handle(body_handle).perp() * self.vel.angle
+    }
+    /// Set my linear velocity such that the given handle moves at `world_vel`.
+    /// A single point under-determines the motion, so my angular velocity is kept.
+    pub fn set_velocity_at(&mut self, body_handle: VecLa, world_vel: VecXy) {
+        self.vel.xy = world_vel - self.xy_relative_handle(body_handle).perp() * self.vel.angle;
+    }
+
+    /// Inspired by https://en.wikipedia.org/wiki/Angular_momentum
+    /// contact: force application point relative to my center of mass, in world units
+    /// force: linear acceleration it causes if applied at my center of mass, i.e., force / mass,
+    /// in world units per tick squared
+    /// The result's angle is in radians per tick squared: torque / inertia.
+    /// Zero if I'm static.
+    fn tug_acc(&self, contact: VecXy, force: VecXy) -> FieldScalars {
+        // (force·mass) / mass, and (contact × force·mass) / inertia
+        self.impulse_response(contact, force * self.mass())
+    }
+}
+
+/// Separates each overlapping pair `[a, b]` of `bodies` among `pairs`, where `a < b`,
+/// reporting each as a collision event
+pub fn resolve_collision_pairs(bodies: &mut [Body], pairs: &[[usize; 2]]) -> Vec<PhysicsEvent> {
+    let mut events = Vec::new();
+    for &[a, b] in pairs {
+        let (before, after) = bodies.split_at_mut(b);
+        let (body_a, body_b) = (&mut before[a], &mut after[0]);
+        if let Some(collision) = body_a.collides_with(body_b) {
+            body_a.resolve_collision(body_b, &collision);
+            let Collision { normal, point, .. } = collision;
+            events.push(PhysicsEvent::Collision { a, b, normal, point });
+        }
+    }
+    events
+}
+
+/// Point the tuggers connecting `bodies` at their targets, and tug each target back equally
+/// over `dt` ticks, accumulating the work of doing so.
+/// Tuggers targeting their own body or a missing one keep their destination.
+pub fn tug_between_bodies(bodies: &mut [Body], dt: f32) {
+    let indices: HashMap<BodyHandle, usize> =
+        bodies.iter().enumerate().map(|(index, body)| (body.id, index)).collect();
+    for index in 0..bodies.len() {
+        for tugger_index in 0..bodies[index].tuggers.len() {
+            let Some(target) = bodies[index].tuggers[tugger_index].to_body.clone() else {
+                continue;
+            };
+            let Some(&target_index) = indices.get(&target.body).filter(|&&i| i != index) else {
+                continue;
+            };
+            let other = &bodies[target_index];
+            let (dest, dest_vel) =
+                (other.absolute_handle(target.handle), other.velocity_at(target.handle));
+
+            let body = &mut bodies[index];
+            // a target moving faster than resting wakes me
+            let dest_travel = body.tuggers[tugger_index].world_dest.distance(dest);
+            if Body::REST_SPEED_XY * dt <= dest_travel {
+                body.wake();
+            }
+            body.tuggers[tugger_index].world_dest = dest;
+            if body.asleep {
+                // sleeping bodies don't tug
+                continue;
+            }
+            let force = body.tug_force(&body.tuggers[tugger_index]).length_capped(body.acc_scalar);
+            // tug forces are given per unit of the tugged body's mass.
+            // Static targets have no inverse mass, so they hold still like a fixed destination.
+            let reaction = -force * body.mass();
+            body.tuggers[tugger_index].work += reaction.dot(dest_vel) * dt;
+
+            let other = &mut bodies[target_index];
+            let acc = other.impulse_response(other.xy_relative_handle(target.handle), reaction);
+            other.vel.add_from(&acc.scaled(dt));
+        }
+    }
+}
+
+impl Scene {
+    /// Built-in scene, used where no scene file exists
+    pub fn fallback(down: f32) -> Self {
+        Scene {
+            gravity: VecXy::new(0., 0.1 * down),
+            bodies: vec![
+                Body {
+                    id: BodyHandle::default(),
+                    acc_scalar: 0.2,
+                    density: Body::DEFAULT_DENSITY,
+                    // statics: VelocityStatics {
+                    //     xy: VelocityStatic {
+                    //         acc_scalar: 0.003,
+                    //         linear_friction_scalar: 0.99,
+                    //         constant_friction: 0.001,
+                    //     },
+                    //     angle: VelocityStatic {
+                    //         acc_scalar: 0.00009,
+                    //         linear_friction_scalar: 0.99,
+                    //         constant_friction: 0.0001,
+                    //     },
+                    // },
+                    pos: FieldScalars { xy: VecXy::splat(300.), angle: 1. },
+                    vel: FieldScalars { xy: VecXy::splat(0.), angle: 0. },
+                    scale: VecXy::new(50., 50.),
+                    shape: Shape::Rect,
+                    rect_scale: None,
+                    color: [1., 0.6, 0.4, 1.],
+                    mouse_tugger: None,
+                    tuggers: vec![
+                        Tugger {
+                            world_dest: VecXy::new(300., 280.),
+                            relative_body_handle_xy: VecLa { length: 7., angle: 2. },
+                            stiffness: Tugger::DEFAULT_STIFFNESS,
+                            damping: Tugger::DEFAULT_DAMPING,
+                            break_force: None,
+                            to_body: None,
+                            work: 0.,
+                        },
+                        // Tugger {
+                        //     world_dest: VecXy::new(400., 220.),
+                        //     relative_body_handle_xy: VecLa { length: 9., angle: 2.4 },
+                        // },
+                    ],
+                    max_tuggers: None,
+                    max_tug_handle_distance: 35.,
+                    ignore_gravity: false,
+                    static_body: false,
+                    acc: FieldScalars::default(),
+                    substeps: None,
+                    restitution: 0.5,
+                    quadratic_drag: 0.,
+                    max_linear_speed: None,
+                    max_angular_speed: None,
+                    trail: VecDeque::new(),
+                    asleep: false,
+                    rest_ticks: 0,
+                    sleep_fade: 0.,
+                },
+                Body {
+                    id: BodyHandle::default(),
+                    // statics: VelocityStatics {
+                    //     xy: VelocityStatic {
+                    //         acc_scalar: 0.002,
+                    //         linear_friction_scalar: 0.99,
+                    //         constant_friction: 0.001,
+                    //     },
+                    //     angle: VelocityStatic {
+                    //         acc_scalar: 0.00007,
+                    //         linear_friction_scalar: 0.99,
+                    //         constant_friction: 0.0001,
+                    //     },
+                    // },
+                    acc_scalar: 0.2,
+                    density: Body::DEFAULT_DENSITY,
+                    pos: FieldScalars { xy: VecXy::splat(300.), angle: 1. },
+                    vel: FieldScalars { xy: VecXy::splat(0.), angle: 0. },
+                    scale: VecXy::new(80., 30.),
+                    shape: Shape::Rect,
+                    rect_scale: None,
+                    color: [0.4, 0.7, 1., 1.],
+                    mouse_tugger: None,
+                    tuggers: vec![
+                        Tugger {
+                            world_dest: VecXy::new(450., 100.),
+                            relative_body_handle_xy: VecLa { length: 35., angle: 0.3 },
+                            stiffness: Tugger::DEFAULT_STIFFNESS,
+                            damping: Tugger::DEFAULT_DAMPING,
+                            break_force: None,
+                            to_body: None,
+                            work: 0.,
+                        },
+                        // Tugger {
+                        //     world_dest: VecXy::new(510., 400.),
+                        //     relative_body_handle_xy: VecLa { length: 30., angle: 3.1 },
+                        // },
+                    ],
+                    max_tuggers: None,
+                    max_tug_handle_distance: 80.,
+                    ignore_gravity: false,
+                    static_body: false,
+                    acc: FieldScalars::default(),
+                    substeps: None,
+                    restitution: 0.5,
+                    quadratic_drag: 0.,
+                    max_linear_speed: None,
+                    max_angular_speed: None,
+                    trail: VecDeque::new(),
+                    asleep: false,
+                    rest_ticks: 0,
+                    sleep_fade: 0.,
+                },
+                Body {
+                    id: BodyHandle::default(),
+                    acc_scalar: 0.2,
+                    density: Body::DEFAULT_DENSITY,
+                    pos: FieldScalars { xy: VecXy::new(200., 150.), angle: 0. },
+                    vel: FieldScalars::default(),
+                    scale: VecXy::splat(40.),
+                    shape: Shape::Circle,
+                    rect_scale: None,
+                    color: [0.6, 1., 0.5, 1.],
+                    mouse_tugger: None,
+                    tuggers: Vec::new(),
+                    max_tuggers: None,
+                    max_tug_handle_distance: 20.,
+                    ignore_gravity: false,
+                    static_body: false,
+                    acc: FieldScalars::default(),
+                    substeps: None,
+                    restitution: 0.5,
+                    quadratic_drag: 0.,
+                    max_linear_speed: None,
+                    max_angular_speed: None,
+                    trail: VecDeque::new(),
+                    asleep: false,
+                    rest_ticks: 0,
+                    sleep_fade: 0.,
+                },
+            ],
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+    /// Read and validate the scene file at `path`, as RON if its extension is `ron`, otherwise
+    /// as JSON
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let scene: Scene = if path.extension().is_some_and(|ext| ext == "ron") {
+            ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        } else {
+            serde_json::from_str(&text)?
+        };
+        for (index, body) in scene.bodies.iter().enumerate() {
+            let distance = body.max_tug_handle_distance;
+            if distance.is_nan() || distance <= 0. {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "body {} has max tug handle distance {}, but it must be positive",
+                        index, distance
+                    ),
+                ));
+            }
+        }
+        Ok(scene)
+    }
+}
+
+impl World {
+    pub const SEED: u64 = 0x5eed;
+    /// Maximum acceleration added to each body per tick when jitter is enabled
+    pub const JITTER_STRENGTH: f32 = 0.005;
+    /// Maximum linear speed added to each body by a shake
+    pub const SHAKE_STRENGTH: f32 = 3.;
+    /// History length suited to rewinding interactively, ten seconds at 60 ticks per second
+    pub const HISTORY_LEN: usize = 600;
+    /// Fraction of its smallest extent a body may travel per adaptive substep
+    pub const MAX_TRAVEL_PER_SUBSTEP: f32 = 0.25;
+    /// Upper bound on adaptive substeps per tick, bounding the smallest step
+    pub const MAX_ADAPTIVE_SUBSTEPS: usize = 16;
+
+    /// Unbounded world of `bodies` under `gravity`
+    pub fn new(bodies: Vec<Body>, gravity: VecXy) -> Self {
+        let mut world = Self {
+            bodies: Vec::new(),
+            gravity,
+            force_fields: Vec::new(),
+            boundary_mode: BoundaryMode::default(),
+            bounds: [VecXy::splat(f32::NEG_INFINITY), VecXy::splat(f32::INFINITY)],
+            integrator: Integrator::default(),
+            substeps: 1,
+            adaptive_timestep: false,
+            jitter: false,
+            grid_cell_size: 100.,
+            trail_length: 0,
+            rng: Rng::new(Self::SEED),
+            ticks: 0,
+            history_len: 0,
+            history: VecDeque::new(),
+            events: Vec::new(),
+            pre_step: None,
+            post_step: None,
+            next_id: 0,
+        };
+        world.set_bodies(bodies);
+        world
+    }
+
+    /// Replace my bodies with `bodies`, keeping their handles unless taken by an earlier one,
+    /// so that tuggers between them stay connected
+    pub fn set_bodies(&mut self, bodies: Vec<Body>) {
+        self.next_id = bodies.iter().map(|body| body.id.0 + 1).max().unwrap_or(0);
+        let mut taken = HashSet::new();
+        self.bodies = bodies;
+        for body in self.bodies.iter_mut() {
+            if !taken.insert(body.id) {
+                body.id = BodyHandle(self.next_id);
+                self.next_id += 1;
+            }
+        }
+    }
+
+    /// Add `body` under a new handle, returned
+    pub fn add_body(&mut self, body: Body) -> BodyHandle {
+        let id = BodyHandle(self.next_id);
+        self.next_id += 1;
+        self.bodies.push(Body { id, ..body });
+        id
+    }
+
+    /// Index of the body with `handle` among my bodies
+    pub fn index_of(&self, handle: BodyHandle) -> Option<usize> {
+        self.bodies.iter().position(|body| body.id == handle)
+    }
+    pub fn get(&self, handle: BodyHandle) -> Option<&Body> {
+        self.bodies.iter().find(|body| body.id == handle)
+    }
+    pub fn get_mut(&mut self, handle: BodyHandle) -> Option<&mut Body> {
+        self.bodies.iter_mut().find(|body| body.id == handle)
+    }
+
+    /// Remove and return the body with `handle`, dropping the tuggers targeting it
+    pub fn remove_body(&mut self, handle: BodyHandle) -> Option<Body> {
+        let removed = self.bodies.remove(self.index_of(handle)?);
+        for body in self.bodies.iter_mut() {
+            body.tuggers.retain(|tugger| tugger.to_body.as_ref().is_none_or(|t| t.body != handle));
+        }
+        Some(removed)
+    }
+
+    /// Substeps needed for no body to travel more than `MAX_TRAVEL_PER_SUBSTEP` of itself per substep
+    pub fn adaptive_substeps(&self) -> usize {
+        let needed = self
+            .bodies
+            .iter()
+            .map(|body| {
+                let max_travel = body.scale.min_element() * Self::MAX_TRAVEL_PER_SUBSTEP;
+                safe_div(body.vel.xy.length(), max_travel, 0.)
+            })
+            .fold(0., f32::max);
+        (needed.ceil() as usize).clamp(1, Self::MAX_ADAPTIVE_SUBSTEPS)
+    }
+
+    /// Run `hook` before each tick's integration, replacing any previous one
+    pub fn set_pre_step(&mut self, hook: Option<StepHook>) {
+        self.pre_step = hook;
+    }
+    /// Run `hook` after each tick's integration, replacing any previous one
+    pub fn set_post_step(&mut self, hook: Option<StepHook>) {
+        self.post_step = hook;
+    }
+
+    /// Advance my bodies by one tick, subject to my force fields and the given ones, which
+    /// apply to this tick only
+    pub fn tick(&mut self, transient_fields: &[&dyn ForceField]) {
+        if 0 < self.history_len {
+            self.history.push_back(self.bodies.clone());
+        }
+        while self.history_len < self.history.len() {
+            self.history.pop_front();
+        }
+        let positions_before: Vec<FieldScalars> =
+            self.bodies.iter().map(|body| body.pos.clone()).collect();
+        self.ticks += 1;
+
+        if let Some(mut hook) = self.pre_step.take() {
+            hook(self);
+            self.pre_step = Some(hook);
+        }
+        let [bounds_min, bounds_max] = self.bounds;
+        let mut substeps = self.substeps.max(1);
+        if self.adaptive_timestep {
+            substeps = substeps.max(self.adaptive_substeps());
+        }
+        tug_between_bodies(&mut self.bodies, 1.);
+        let gravity = Gravity { acc: self.gravity };
+        let fields: Vec<&dyn ForceField> = std::iter::once(&gravity as &dyn ForceField)
+            .chain(self.force_fields.iter().map(|field| field.as_ref()))
+            .chain(transient_fields.iter().copied())
+            .collect();
+        for body in self.bodies.iter_mut().filter(|body| !body.static_body && !body.asleep) {
+            // bodies otherwise only interact via collisions, so each can be integrated on its own
+            let substeps = body.substeps.unwrap_or(substeps).max(1);
+            let dt = 1. / substeps as f32;
+            for _ in 0..substeps {
+                if self.jitter {
+                    body.vel.xy += self.rng.vec_xy(Self::JITTER_STRENGTH) * dt;
+                }
+                body.step(dt, &fields, self.integrator);
+                match self.boundary_mode {
+                    BoundaryMode::Walls => body.bounce_within(bounds_min, bounds_max),
+                    BoundaryMode::Clamp => body.clamp_within(bounds_min, bounds_max),
+                    BoundaryMode::Wrap => body.wrap_within(bounds_min, bounds_max),
+                    BoundaryMode::None => {}
+                }
+            }
+        }
+        let pairs = SpatialGrid::new(&self.bodies, self.grid_cell_size).candidate_pairs();
+        self.events = resolve_collision_pairs(&mut self.bodies, &pairs);
+        for body in self.bodies.iter_mut() {
+            body.clamp_speeds();
+            body.update_sleep();
+            body.record_trail(self.trail_length);
+        }
+        // quarantine bodies poisoned with NaN or infinity, returning them to their previous position
+        for (index, body) in self.bodies.iter_mut().enumerate() {
+            let fallback = positions_before.get(index).cloned().unwrap_or_default();
+            if body.recover_finite(&fallback) {
+                eprintln!("warning: body {} became non-finite and was stopped", index);
+            }
+        }
+        if let Some(mut hook) = self.post_step.take() {
+            hook(self);
+            self.post_step = Some(hook);
+        }
+    }
+
+    /// Advance my bodies by `steps` ticks, without rendering.
+    /// Deterministic: the same world always ends up the same.
+    pub fn simulate(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.tick(&[]);
+        }
+    }
+
+    /// Restore the snapshot from before the most recent tick, if any
+    pub fn rewind(&mut self) {
+        if let Some(bodies) = self.history.pop_back() {
+            self.bodies = bodies;
+            self.ticks = self.ticks.saturating_sub(1);
+            for body in self.bodies.iter_mut() {
+                body.mouse_tugger = None;
+            }
+        }
+    }
+
+    pub fn config(&self) -> PhysicsConfig {
+        PhysicsConfig {
+            gravity: self.gravity,
+            boundary_mode: self.boundary_mode,
+            bounds: self.bounds,
+            integrator: self.integrator,
+            substeps: self.substeps,
+            adaptive_timestep: self.adaptive_timestep,
+            jitter: self.jitter,
+            grid_cell_size: self.grid_cell_size,
+            trail_length: self.trail_length,
+        }
+    }
+    pub fn set_config(&mut self, config: &PhysicsConfig) {
+        self.gravity = config.gravity;
+        self.boundary_mode = config.boundary_mode;
+        self.bounds = config.bounds;
+        self.integrator = config.integrator;
+        self.substeps = config.substeps;
+        self.adaptive_timestep = config.adaptive_timestep;
+        self.jitter = config.jitter;
+        self.grid_cell_size = config.grid_cell_size;
+        self.trail_length = config.trail_length;
+    }
+
+    /// My current state, from which `restore` resumes me
+    pub fn repro(&self) -> Repro {
+        Repro {
+            config: self.config(),
+            rng: self.rng.clone(),
+            ticks: self.ticks,
+            bodies: self.bodies.clone(),
+        }
+    }
+    /// Resume from the state of `repro`, forgetting my history
+    pub fn restore(&mut self, repro: Repro) {
+        self.set_config(&repro.config);
+        self.rng = repro.rng;
+        self.ticks = repro.ticks;
+        self.set_bodies(repro.bodies);
+        self.history.clear();
+    }
+
+    /// Apply a random one-frame impulse at every body's center of mass, leaving static ones be
+    pub fn shake(&mut self) {
+        for body in self.bodies.iter_mut() {
+            body.apply_impulse(body.pos.xy, self.rng.vec_xy(Self::SHAKE_STRENGTH));
+        }
+    }
+
+    /// Swap the x and y of my bodies' tugger destinations, leaving the first body alone unless
+    /// `swap_all` is set
+    pub fn swap_dests(&mut self, swap_all: bool) {
+        let skip = if swap_all { 0 } else { 1 };
+        for body in self.bodies.iter_mut().skip(skip) {
+            for tugger in body.tuggers.iter_mut() {
+                let [x, y]: [f32; 2] = tugger.world_dest.into();
+                tugger.world_dest = VecXy::new(y, x);
+            }
+        }
+    }
+
+    /// Center and text of the label of each body, its index as in event logs
+    pub fn labels(&self) -> impl Iterator<Item = (VecXy, String)> + '_ {
+        self.bodies.iter().enumerate().map(|(index, body)| (body.pos.xy, index.to_string()))
+    }
+
+    /// Bound me to the box of the given min corner and size, e.g., a screen rectangle
+    pub fn set_bounds_rect(&mut self, min: VecXy, size: VecXy) {
+        self.bounds = [min, min + size];
+    }
+    /// Min corner and size of my bounds, none while unbounded
+    pub fn bounds_rect(&self) -> Option<[VecXy; 2]> {
+        let [min, max] = self.bounds;
+        (min.is_finite() && max.is_finite()).then(|| [min, max - min])
+    }
+
+    /// [min, max] corners of the axis-aligned box around all my bodies, if any
+    pub fn aabb(&self) -> Option<[VecXy; 2]> {
+        self.bodies
+            .iter()
+            .map(|body| {
+                let half = body.aabb_half_extents();
+                [body.pos.xy - half, body.pos.xy + half]
+            })
+            .reduce(|[min, max], [body_min, body_max]| [min.min(body_min), max.max(body_max)])
+    }
+
+    /// Index of the topmost body containing `world_xy`, i.e., the last one drawn
+    pub fn body_at(&self, world_xy: VecXy) -> Option<usize> {
+        self.bodies.iter().rposition(|body| body.contains(world_xy))
+    }
+
+    /// Index and center distance of the body whose center is nearest to `p`
+    pub fn nearest_body(&self, p: VecXy) -> Option<(usize, f32)> {
+        self.bodies
+            .iter()
+            .map(|body| body.pos.xy.distance(p))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Summed over my bodies, except static ones, whose mass is infinite
+    pub fn total_linear_momentum(&self) -> VecXy {
+        self.dynamic_bodies().map(Body::momentum).sum()
+    }
+    /// Summed over my bodies, except static ones
+    pub fn total_kinetic_energy(&self) -> f32 {
+        self.dynamic_bodies().map(Body::kinetic_energy).sum()
+    }
+    fn dynamic_bodies(&self) -> impl Iterator<Item = &Body> + '_ {
+        self.bodies.iter().filter(|body| !body.static_body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square body of the given side at `xy`, at rest
+    fn square(xy: VecXy, side: f32) -> Body {
+        let half = VecXy::splat(side * 0.5);
+        Body::new_spanning(xy - half, xy + half)
+    }
+
+    #[test]
+    fn at_rest_requires_both_speeds_below_thresholds() {
+        let mut moving = square(VecXy::ZERO, 10.);
+        moving.vel.xy = VecXy::new(1., 0.);
+        let mut spinning = square(VecXy::ZERO, 10.);
+        spinning.vel.angle = 0.01;
+        let mut drifting = square(VecXy::ZERO, 10.);
+        drifting.vel = FieldScalars { xy: VecXy::new(0.01, -0.01), angle: 0.0001 };
+        let still = square(VecXy::ZERO, 10.);
+        let bodies = [moving, spinning, drifting, still];
+        let at_rest: Vec<bool> = bodies.iter().map(Body::at_rest).collect();
+        assert_eq!(at_rest, [false, false, true, true]);
+    }
+
+    #[test]
+    fn rewinding_restores_earlier_snapshots() {
+        let mut falling = square(VecXy::new(0., -40.), 20.);
+        falling.vel.angle = 0.05;
+        let mut world = World::new(vec![falling, square(VecXy::ZERO, 30.)], VecXy::new(0., 0.1));
+        world.history_len = World::HISTORY_LEN;
+        let mut positions = vec![world.bodies[0].pos.clone()];
+        for _ in 0..10 {
+            world.tick(&[]);
+            positions.push(world.bodies[0].pos.clone());
+        }
+        for _ in 0..4 {
+            world.rewind();
+        }
+        assert_eq!(world.ticks, 6);
+        assert_eq!(world.bodies[0].pos.xy, positions[6].xy);
+        assert_eq!(world.bodies[0].pos.angle, positions[6].angle);
+
+        world.simulate(World::HISTORY_LEN as u32);
+        assert_eq!(world.history.len(), World::HISTORY_LEN);
+
+        // worlds keep no history by default
+        world.history_len = 0;
+        world.tick(&[]);
+        assert!(world.history.is_empty());
+    }
+
+    #[test]
+    fn substeps_stabilize_stiff_tuggers() {
+        let offset_after = |substeps| {
+            let mut body = square(VecXy::ZERO, 20.);
+            // uncapped, so nothing but the integration bounds the force
+            body.acc_scalar = f32::MAX;
+            body.tuggers.push(Tugger { stiffness: 5., ..center_tugger(VecXy::new(10., 0.)) });
+            let mut world = World::new(vec![body], VecXy::ZERO);
+            world.substeps = substeps;
+            world.simulate(20);
+            world.bodies[0].pos.xy.distance(VecXy::new(10., 0.))
+        };
+        assert!(1e3 < offset_after(1));
+        assert!(offset_after(4) <= 10.);
+        assert!(offset_after(8) <= 10.);
+
+        // the same gravity per tick, however it's split
+        let velocity_after = |substeps| {
+            let mut world = World::new(vec![square(VecXy::ZERO, 20.)], VecXy::new(0., 0.1));
+            world.substeps = substeps;
+            world.simulate(20);
+            world.bodies[0].vel.xy
+        };
+        assert!(velocity_after(1).distance(VecXy::new(0., 2.)) < 1e-5);
+        assert!(velocity_after(8).distance(velocity_after(1)) < 1e-5);
+    }
+
+    #[test]
+    fn nearest_body_is_by_center_distance() {
+        let mut world = World::new(Vec::new(), VecXy::ZERO);
+        assert_eq!(world.nearest_body(VecXy::ZERO), None);
+        world.bodies = vec![
+            square(VecXy::new(-50., 0.), 10.),
+            square(VecXy::new(30., 40.), 60.),
+            square(VecXy::new(0., 20.), 10.),
+        ];
+        assert_eq!(world.nearest_body(VecXy::new(0., 5.)), Some((2, 15.)));
+        assert_eq!(world.nearest_body(VecXy::new(30., 10.)), Some((1, 30.)));
+    }
+
+    #[test]
+    fn smoothing_moves_destinations_part_way() {
+        let mut tugger = center_tugger(VecXy::ZERO);
+        let target = VecXy::new(100., -20.);
+        tugger.smooth_dest_toward(target, 0.25);
+        assert_eq!(tugger.world_dest, VecXy::new(25., -5.));
+        tugger.smooth_dest_toward(target, 0.5);
+        assert_eq!(tugger.world_dest, VecXy::new(62.5, -12.5));
+        tugger.smooth_dest_toward(target, 1.);
+        assert_eq!(tugger.world_dest, target);
+    }
+
+    #[test]
+    fn safe_div_falls_back_on_zero_denominators() {
+        assert_eq!(safe_div(6., 3., 0.), 2.);
+        assert_eq!(safe_div(1., 0., 7.), 7.);
+        assert_eq!(safe_div(-1., 0., 7.), 7.);
+        assert_eq!(safe_div(0., 0., 7.), 7.);
+    }
+
+    #[test]
+    fn zero_handle_distance_stays_finite() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.max_tug_handle_distance = 0.;
+        body.tuggers.push(center_tugger(VecXy::new(50., 10.)));
+        let handle = body.grab_handle(VecXy::new(3., 4.));
+        assert_eq!(handle.length, 0.);
+        assert!(handle.angle.is_finite());
+        body.perturb_constants(&mut Rng::new(World::SEED), 0.2);
+        for _ in 0..10 {
+            body.step(1., &[], Integrator::default());
+        }
+        assert!(body.pos.is_finite() && body.vel.is_finite());
+    }
+
+    #[test]
+    fn snapping_puts_the_lowest_corner_on_the_floor() {
+        let mut body = Body::new_spanning(VecXy::new(-20., -10.), VecXy::new(20., 10.));
+        body.pos.angle = 0.3;
+        body.vel = FieldScalars { xy: VecXy::new(1., 2.), angle: 0.1 };
+        let corners = |body: &Body| {
+            let half = body.scale * 0.5;
+            [VecXy::new(-1., -1.), VecXy::new(-1., 1.), VecXy::new(1., -1.), VecXy::new(1., 1.)]
+                .map(|sign| body.pos.xy + (half * sign).rotated(body.pos.angle))
+        };
+
+        body.snap_to_floor(100., 1.);
+        let lowest = corners(&body).iter().map(|corner| corner.y).fold(f32::MIN, f32::max);
+        assert!((lowest - 100.).abs() < 1e-4);
+        assert_eq!((body.vel.xy, body.vel.angle), (VecXy::ZERO, 0.));
+
+        // with y pointing up, the floor is below
+        body.snap_to_floor(-100., -1.);
+        let lowest = corners(&body).iter().map(|corner| corner.y).fold(f32::MAX, f32::min);
+        assert!((lowest + 100.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mirroring_twice_restores_the_scene() {
+        let mut body = square(VecXy::new(-30., 12.), 20.);
+        body.pos.angle = 0.7;
+        body.vel = FieldScalars { xy: VecXy::new(1.5, -2.), angle: 0.03 };
+        body.tuggers.push(Tugger {
+            relative_body_handle_xy: VecLa { length: 5., angle: 2. },
+            to_body: Some(TugTarget {
+                body: BodyHandle(1),
+                handle: VecLa { length: 3., angle: -1. },
+            }),
+            ..center_tugger(VecXy::new(80., -7.))
+        });
+        body.mouse_tugger = Some(center_tugger(VecXy::new(-5., 5.)));
+        let mut bodies = vec![body, square(VecXy::new(100., 0.), 10.)];
+        let original = bodies.clone();
+
+        for _ in 0..2 {
+            for body in bodies.iter_mut() {
+                body.mirror_x(40.);
+            }
+        }
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        for (body, was) in bodies.iter().zip(&original) {
+            assert!(
+                body.pos.xy.distance(was.pos.xy) < 1e-4 && close(body.pos.angle, was.pos.angle)
+            );
+            assert!(
+                body.vel.xy.distance(was.vel.xy) < 1e-4 && close(body.vel.angle, was.vel.angle)
+            );
+            for (tugger, was) in body.all_tuggers().zip(was.all_tuggers()) {
+                assert!(tugger.world_dest.distance(was.world_dest) < 1e-4);
+                let [handle, was_handle] =
+                    [tugger, was].map(|tugger| tugger.relative_body_handle_xy.to_xy());
+                assert!(handle.distance(was_handle) < 1e-4);
+                let [target, was_target] =
+                    [tugger, was].map(|tugger| tugger.to_body.as_ref().map(|t| t.handle.to_xy()));
+                assert!(target.zip(was_target).is_none_or(|(a, b)| a.distance(b) < 1e-4));
+            }
+        }
+    }
+
+    #[test]
+    fn spinning_keeps_angles_within_half_turns() {
+        use std::f32::consts::PI;
+        let mut spinning = square(VecXy::ZERO, 20.);
+        spinning.vel.angle = 0.3;
+        let mut world = World::new(vec![spinning], VecXy::ZERO);
+        for _ in 0..10_000 {
+            world.tick(&[]);
+            let angle = world.bodies[0].pos.angle;
+            assert!((-PI..=PI).contains(&angle), "angle {} out of range", angle);
+        }
+        assert_eq!(world.bodies[0].vel.angle, 0.3);
+        assert!((7. * PI).wrapped_angle() + PI < 1e-5);
+        assert!(((-2.5 * PI).wrapped_angle() + 0.5 * PI).abs() < 1e-5);
+    }
+
+    #[test]
+    fn vec_la_lerp_takes_the_short_way_around() {
+        use std::f32::consts::PI;
+        let from = VecLa { length: 2., angle: 3. };
+        let to = VecLa { length: 4., angle: -3. };
+        let half = from.lerp(to, 0.5);
+        assert!((half.length - 3.).abs() < 1e-6);
+        // midway across ±π, rather than through 0
+        assert!((half.angle.wrapped_angle().abs() - PI).abs() < 1e-5);
+        let end = from.lerp(to, 1.);
+        assert!((end.angle - to.angle).wrapped_angle().abs() < 1e-5);
+        assert_eq!(from.lerp(to, 0.).angle, from.angle);
+
+        let rotated = from.rotated(0.5);
+        assert_eq!((rotated.length, rotated.angle), (2., 3.5));
+    }
+
+    #[test]
+    fn grid_finds_the_same_collisions_as_brute_force() {
+        let mut rng = Rng::new(World::SEED);
+        let bodies: Vec<Body> = (0..60)
+            .map(|i| {
+                let xy = VecXy::new(rng.range(0., 300.), rng.range(-150., 150.));
+                let half = VecXy::new(rng.range(2.5, 20.), rng.range(2.5, 20.));
+                let mut body = Body::new_spanning(xy - half, xy + half);
+                body.pos.angle = rng.range(-3., 3.);
+                if i % 3 == 0 {
+                    body.toggle_shape();
+                }
+                body
+            })
+            .collect();
+        let colliding = |&[a, b]: &[usize; 2]| bodies[a].collides_with(&bodies[b]).is_some();
+        let brute_force: Vec<[usize; 2]> =
+            (0..bodies.len()).flat_map(|b| (0..b).map(move |a| [a, b])).filter(colliding).collect();
+        assert!(!brute_force.is_empty());
+        // unusable cell sizes fall back to a single cell
+        for cell_size in [10., 45., 500., 0., -1., f32::NAN] {
+            let pairs = SpatialGrid::new(&bodies, cell_size).candidate_pairs();
+            let found: Vec<[usize; 2]> = pairs.into_iter().filter(colliding).collect();
+            assert_eq!(found, brute_force);
+        }
+    }
+
+    #[test]
+    fn a_single_tugger_settles_its_handle_at_its_destination() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.tuggers.push(Tugger {
+            relative_body_handle_xy: VecLa { length: 6., angle: 1. },
+            ..center_tugger(VecXy::new(120., -45.))
+        });
+        let mut world = World::new(vec![body], VecXy::ZERO);
+        world.simulate(10_000);
+        let body = &world.bodies[0];
+        let tugger = &body.tuggers[0];
+        let handle = body.absolute_handle(tugger.relative_body_handle_xy);
+        assert!(handle.distance(tugger.world_dest) < 2.);
+    }
+
+    #[test]
+    fn step_timings_summarize_each_window() {
+        let mut timings = StepTimings::default();
+        assert_eq!(timings.stats(), None);
+        let window: Vec<Duration> =
+            (1..=StepTimings::WINDOW as u64).map(Duration::from_millis).collect();
+        for &sample in &window[..StepTimings::WINDOW - 1] {
+            assert_eq!(timings.record(sample), None);
+        }
+        let mean = Duration::from_micros(500 * (StepTimings::WINDOW as u64 + 1));
+        assert_eq!(
+            timings.record(window[StepTimings::WINDOW - 1]),
+            Some([Duration::from_millis(1), mean, window[StepTimings::WINDOW - 1]])
+        );
+        // the next window starts afresh
+        assert_eq!(timings.record(Duration::from_millis(7)), None);
+        assert_eq!(timings.stats(), Some([Duration::from_millis(7); 3]));
+    }
+
+    #[test]
+    fn stalls_run_at_most_the_maximum_ticks() {
+        let mut timestep = FixedTimestep::new(0.25, 5);
+        assert_eq!(timestep.ticks_due(0.625), 2);
+        assert_eq!(timestep.accumulator, 0.125);
+        assert_eq!(timestep.ticks_due(0.125), 1);
+        // a frame taking minutes only catches up on the maximum, dropping the rest
+        assert_eq!(timestep.ticks_due(300.), 5);
+        assert_eq!(timestep.accumulator, 0.);
+        timestep.max_ticks_per_frame = 2;
+        assert_eq!(timestep.ticks_due(1e9), 2);
+        assert_eq!(timestep.ticks_due(0.25), 1);
+    }
+
+    #[test]
+    fn radial_tugs_exert_no_torque() {
+        let body = square(VecXy::ZERO, 20.);
+        for contact in [VecXy::new(5., 0.), VecXy::new(-3., 4.)] {
+            for force in [contact * 0.1, contact * -2.] {
+                let acc = body.tug_acc(contact, force);
+                assert_eq!(acc.angle, 0.);
+                assert!(acc.xy.distance(force) < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn tangential_tugs_exert_r_cross_f_torque() {
+        let body = square(VecXy::ZERO, 20.);
+        let r = 7.;
+        let force = 0.3;
+        // forces are given per unit of mass, so torques are too
+        let torque = |contact: VecXy, force: VecXy| {
+            body.tug_acc(contact, force).angle * body.inertia() / body.mass()
+        };
+        let expected = r * force;
+        // counterclockwise with y up, i.e., toward positive angles
+        assert!((torque(VecXy::new(r, 0.), VecXy::new(0., force)) - expected).abs() < 1e-5);
+        assert!((torque(VecXy::new(r, 0.), VecXy::new(0., -force)) + expected).abs() < 1e-5);
+        assert!((torque(VecXy::new(0., r), VecXy::new(force, 0.)) + expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn only_explicit_euler_diverges_under_stiff_springs() {
+        let offset_after = |integrator| {
+            let mut body = square(VecXy::ZERO, 20.);
+            body.acc_scalar = f32::MAX;
+            body.tuggers.push(Tugger {
+                stiffness: 0.5,
+                damping: 0.,
+                ..center_tugger(VecXy::new(10., 0.))
+            });
+            for _ in 0..200 {
+                body.step(1., &[], integrator);
+            }
+            body.pos.xy.distance(VecXy::new(10., 0.))
+        };
+        assert!(1e3 < offset_after(Integrator::ExplicitEuler));
+        assert!(offset_after(Integrator::SemiImplicitEuler) < 20.);
+        assert!(offset_after(Integrator::Verlet) < 20.);
+    }
+
+    #[test]
+    fn bodies_poisoned_by_nan_forces_recover() {
+        struct Poison;
+        impl ForceField for Poison {
+            fn accel_at(&self, _: &Body) -> FieldScalars {
+                FieldScalars { xy: VecXy::splat(f32::NAN), angle: f32::NAN }
+            }
+        }
+        let mut body = square(VecXy::new(3., 4.), 20.);
+        body.vel = FieldScalars { xy: VecXy::new(1., 0.), angle: 0.1 };
+        let mut world = World::new(vec![body], VecXy::ZERO);
+        world.tick(&[&Poison]);
+        let body = &world.bodies[0];
+        assert!(body.pos.is_finite());
+        assert_eq!((body.vel.xy, body.vel.angle), (VecXy::ZERO, 0.));
+        // back where it was before the poisoned tick
+        assert_eq!(body.pos.xy, VecXy::new(3., 4.));
+    }
+
+    #[test]
+    fn damping_settles_tuggers_sooner() {
+        // ticks until the handle stays within a unit of its destination, if it does
+        let settling_ticks = |damping| {
+            let mut body = square(VecXy::ZERO, 20.);
+            body.tuggers.push(Tugger { damping, ..center_tugger(VecXy::new(100., 0.)) });
+            let mut settled_at = None;
+            for tick in 0..3000 {
+                body.step(1., &[], Integrator::default());
+                let near = body.pos.xy.distance(VecXy::new(100., 0.)) < 1.;
+                settled_at = if near { settled_at.or(Some(tick)) } else { None };
+            }
+            settled_at
+        };
+        let damped = settling_ticks(Tugger::DEFAULT_DAMPING).expect("damped tuggers settle");
+        assert!(settling_ticks(0.).is_none_or(|undamped| damped < undamped));
+    }
+
+    #[test]
+    fn tuggers_break_once_overstrained() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.tuggers.push(Tugger { break_force: Some(1.), ..center_tugger(VecXy::ZERO) });
+        let mut broke = false;
+        for tick in 1..=200 {
+            let Some(tugger) = body.tuggers.first_mut() else {
+                break;
+            };
+            tugger.world_dest = VecXy::new(tick as f32 * 20., 0.);
+            let overstrained = body.tug_force(&body.tuggers[0]).length() > 1.;
+            body.step(1., &[], Integrator::default());
+            assert_eq!(body.tuggers.is_empty(), overstrained);
+            broke = overstrained;
+        }
+        assert!(broke);
+    }
+
+    #[test]
+    fn speeds_stay_within_their_caps() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.acc_scalar = 50.;
+        body.max_linear_speed = Some(0.5);
+        body.max_angular_speed = Some(0.01);
+        // off center, so it spins as well
+        body.tuggers.push(Tugger {
+            relative_body_handle_xy: VecLa { length: 8., angle: 1. },
+            stiffness: 1.,
+            ..center_tugger(VecXy::new(1e4, -1e4))
+        });
+        let mut world = World::new(vec![body], VecXy::new(0., 3.));
+        for _ in 0..500 {
+            world.tick(&[]);
+            let vel = &world.bodies[0].vel;
+            assert!(vel.xy.length() <= 0.5 + 1e-6);
+            assert!(vel.angle.abs() <= 0.01);
+        }
+        assert!(world.bodies[0].pos.xy.length() > 100.);
+    }
+
+    #[test]
+    fn quadratic_drag_gives_a_terminal_speed() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.quadratic_drag = 0.001;
+        let mut world = World::new(vec![body], VecXy::new(0., 0.1));
+        // where drag balances gravity
+        let terminal = (0.1f32 / 0.001).sqrt();
+        let mut speed = 0.;
+        for _ in 0..2000 {
+            world.tick(&[]);
+            let now = world.bodies[0].vel.xy.length();
+            assert!(speed <= now && now <= terminal);
+            speed = now;
+        }
+        assert!((speed - terminal).abs() < 0.01 * terminal);
+    }
+
+    #[test]
+    fn bounding_circles_never_rule_out_overlaps() {
+        let mut rng = Rng::new(299);
+        let mut overlaps = 0;
+        for i in 0..5000 {
+            let mut a = square(VecXy::ZERO, 1.);
+            let mut b = square(VecXy::ZERO, 1.);
+            for body in [&mut a, &mut b] {
+                body.scale = VecXy::new(rng.range(0.1, 30.), rng.range(0.1, 30.));
+                body.pos.angle = rng.range(-3.2, 3.2);
+            }
+            if i % 3 == 0 {
+                a.toggle_shape();
+            }
+            if i % 5 == 0 {
+                b.toggle_shape();
+            }
+            // mostly near where the bounding circles touch
+            let reach = a.bounding_radius() + b.bounding_radius();
+            b.pos.xy = rng.vec_xy(1.).normalize_or_zero() * reach * rng.range(0.5, 1.01);
+            let exact = a.shape_collision(&b);
+            overlaps += exact.is_some() as usize;
+            assert_eq!(a.collides_with(&b).is_some(), exact.is_some());
+        }
+        assert!(overlaps > 100);
+    }
+
+    #[test]
+    fn handles_follow_bodies_across_deletes_and_rewinds() {
+        let mut world = World::new(
+            vec![square(VecXy::new(-50., 0.), 10.), square(VecXy::new(50., 0.), 20.)],
+            VecXy::ZERO,
+        );
+        world.history_len = 1;
+        let (first, second) = (world.bodies[0].id, world.bodies[1].id);
+        world.tick(&[]);
+        world.remove_body(first);
+        assert_eq!(world.get(second).unwrap().scale, VecXy::splat(20.));
+        let spawned = world.add_body(square(VecXy::new(0., 80.), 5.));
+        world.rewind();
+        // the deleted body is back, the spawned one is gone, and the rest are unmoved
+        assert_eq!(world.get(first).unwrap().scale, VecXy::splat(10.));
+        assert_eq!(world.get(second).unwrap().scale, VecXy::splat(20.));
+        assert!(world.get(spawned).is_none());
+    }
+
+    #[test]
+    fn resting_bodies_fall_asleep_and_stop_integrating() {
+        let mut world = World::new(vec![square(VecXy::ZERO, 20.)], VecXy::ZERO);
+        world.bodies[0].vel.xy = VecXy::new(Body::REST_SPEED_XY * 0.5, 0.);
+        for _ in 1..Body::SLEEP_TICKS {
+            world.tick(&[]);
+        }
+        assert!(!world.bodies[0].asleep);
+        world.tick(&[]);
+        assert!(world.bodies[0].asleep);
+
+        // a drift too slow to wake me is no longer integrated
+        world.bodies[0].vel.xy = VecXy::new(Body::REST_SPEED_XY * 0.5, 0.);
+        let asleep_xy = world.bodies[0].pos.xy;
+        for _ in 0..100 {
+            world.tick(&[]);
+        }
+        assert!(world.bodies[0].asleep);
+        assert_eq!(world.bodies[0].pos.xy, asleep_xy);
+    }
+
+    #[test]
+    fn following_cameras_center_their_target() {
+        let screen_center = VecXy::new(400., 300.);
+        let target = VecXy::new(-1200., 800.);
+        let mut camera = Camera { zoom: 2., ..Camera::default() };
+        let mut distances = Vec::new();
+        for _ in 0..100 {
+            camera.follow(target, screen_center, 0.1);
+            distances.push(camera.world_to_screen(screen_center, target).distance(screen_center));
+        }
+        // closing in a little each step
+        assert!(distances.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(distances[distances.len() - 1] < 0.1);
+    }
+
+    #[test]
+    fn configs_carry_bounds() {
+        let mut bounded = World::new(Vec::new(), VecXy::ZERO);
+        bounded.bounds = [VecXy::new(-10., -20.), VecXy::new(30., 40.)];
+        let mut world = World::new(Vec::new(), VecXy::ZERO);
+        world.set_config(&bounded.config());
+        assert_eq!(world.bounds, bounded.bounds);
+        // unbounded worlds stay unbounded, even if JSON couldn't store them
+        world.set_config(&World::new(Vec::new(), VecXy::ZERO).config());
+        assert_eq!(world.bounds, [VecXy::splat(f32::NEG_INFINITY), VecXy::splat(f32::INFINITY)]);
+    }
+
+    /// Bindings of each action to its own number
+    fn numbered_bindings() -> KeyBindings<usize> {
+        KeyBindings {
+            quit: 0,
+            toggle_draw_mode: 1,
+            cycle_boundary_mode: 2,
+            shake: 3,
+            toggle_swap_all: 4,
+            toggle_ignore_gravity: 5,
+            rotate_ccw: 6,
+            rotate_cw: 7,
+            toggle_jitter: 8,
+            toggle_rope_colors: 9,
+            toggle_wireframe: 10,
+            toggle_debug_draw: 11,
+            toggle_pause: 12,
+            rewind: 13,
+            swap_dests: 14,
+            snap_to_floor: 15,
+            toggle_step_timings: 16,
+            mirror_scene: 17,
+            toggle_adaptive_timestep: 18,
+            toggle_auto_pause: 19,
+            randomize_constants: 20,
+            toggle_labels: 21,
+            toggle_bounds: 22,
+            toggle_gravity: 23,
+            save_scene: 24,
+            load_scene: 25,
+            reset: 26,
+            spawn_body: 27,
+            delete_body: 28,
+            toggle_speed_heatmap: 29,
+            slow_down: 30,
+            speed_up: 31,
+            step_once: 32,
+            toggle_trails: 33,
+            toggle_wind: 34,
+            toggle_static: 35,
+            reset_camera: 36,
+            cycle_integrator: 37,
+            toggle_viscous_pool: 38,
+            flip_y_axis: 39,
+            pin_tugger: 40,
+            toggle_shape: 41,
+            save_repro: 42,
+            load_repro: 43,
+        }
+    }
+
+    #[test]
+    fn rebound_keys_trigger_their_action() {
+        let mut bindings = numbered_bindings();
+        let default_key = bindings.toggle_pause;
+        assert_eq!(bindings.action(default_key), Some(Action::TogglePause));
+        bindings.toggle_pause = 100;
+        assert_eq!(bindings.action(100), Some(Action::TogglePause));
+        assert_eq!(bindings.action(default_key), None);
+        // sharing a key, the earlier binding wins
+        bindings.toggle_pause = bindings.quit;
+        assert_eq!(bindings.action(bindings.quit), Some(Action::Quit));
+    }
+
+    #[test]
+    fn fast_bodies_get_more_substeps_up_to_a_cap() {
+        let mut world = World::new(vec![square(VecXy::ZERO, 20.)], VecXy::ZERO);
+        assert_eq!(world.adaptive_substeps(), 1);
+        // a quarter of its side, 5, per substep
+        world.bodies[0].vel.xy = VecXy::new(12., 0.);
+        assert_eq!(world.adaptive_substeps(), 3);
+        world.bodies[0].vel.xy = VecXy::new(0., -1e4);
+        assert_eq!(world.adaptive_substeps(), World::MAX_ADAPTIVE_SUBSTEPS);
+    }
+
+    #[test]
+    fn per_body_substeps_stabilize_only_their_body() {
+        let stiff_body = |x: f32, substeps| {
+            let mut body = square(VecXy::new(x, 0.), 20.);
+            body.acc_scalar = f32::MAX;
+            body.substeps = substeps;
+            body.tuggers.push(Tugger { stiffness: 5., ..center_tugger(VecXy::new(x + 10., 0.)) });
+            body
+        };
+        // far enough apart never to collide while settling
+        let bodies = vec![stiff_body(-1e4, Some(4)), stiff_body(1e4, None)];
+        let mut world = World::new(bodies, VecXy::ZERO);
+        world.simulate(20);
+        let offsets: Vec<f32> = world
+            .bodies
+            .iter()
+            .map(|body| body.pos.xy.distance(VecXy::new(body.tuggers[0].world_dest.x, 0.)))
+            .collect();
+        assert!(offsets[0] <= 10.);
+        assert!(1e3 < offsets[1]);
+    }
+
+    #[test]
+    fn auto_pause_waits_for_rest_and_resumes_on_grabs() {
+        let mut bodies = vec![square(VecXy::ZERO, 20.), square(VecXy::new(50., 0.), 20.)];
+        let mut auto_pause = AutoPause { enabled: true, ..AutoPause::default() };
+        for _ in 1..AutoPause::TICKS {
+            auto_pause.update(&bodies);
+        }
+        assert!(!auto_pause.paused);
+        auto_pause.update(&bodies);
+        assert!(auto_pause.paused);
+
+        bodies[1].mouse_tugger = Some(center_tugger(VecXy::new(60., 0.)));
+        auto_pause.update(&bodies);
+        assert!(!auto_pause.paused);
+        assert_eq!(auto_pause.ticks_at_rest, 0);
+    }
+
+    #[test]
+    fn jitter_breaks_symmetric_deadlocks() {
+        let settled_y = |jitter| {
+            // pulled equally left and right, nothing ever pushes me up or down
+            let mut body = square(VecXy::ZERO, 20.);
+            body.tuggers.push(center_tugger(VecXy::new(-50., 0.)));
+            body.tuggers.push(center_tugger(VecXy::new(50., 0.)));
+            let mut world = World::new(vec![body], VecXy::ZERO);
+            world.jitter = jitter;
+            world.simulate(30);
+            world.bodies[0].pos.xy.y
+        };
+        assert_eq!(settled_y(false), 0.);
+        assert_ne!(settled_y(true), 0.);
+    }
+
+    #[test]
+    fn perturbed_constants_are_seeded_and_within_spread() {
+        let original = square(VecXy::ZERO, 20.);
+        let perturbed = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..50)
+                .map(|_| {
+                    let mut body = original.clone();
+                    body.perturb_constants(&mut rng, 0.2);
+                    [body.acc_scalar, body.density, body.max_tug_handle_distance]
+                })
+                .collect::<Vec<_>>()
+        };
+        let runs = perturbed(7);
+        assert_eq!(runs, perturbed(7));
+        let base = [original.acc_scalar, original.density, original.max_tug_handle_distance];
+        for run in runs {
+            for (value, base) in run.into_iter().zip(base) {
+                assert!((0.8 * base..=1.2 * base).contains(&value));
+            }
+        }
+    }
+
+    #[test]
+    fn validation_reports_each_overlong_handle_once() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.max_tug_handle_distance = 10.;
+        for length in [5., 12., 10.] {
+            body.tuggers.push(Tugger {
+                relative_body_handle_xy: VecLa { length, angle: 0. },
+                ..center_tugger(VecXy::ZERO)
+            });
+        }
+        assert_eq!(
+            body.validate(),
+            ["tugger 1 handle length 12 exceeds max tug handle distance 10"]
+        );
+    }
+
+    #[test]
+    fn cloned_bodies_are_independent() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.tuggers.push(center_tugger(VecXy::new(30., 0.)));
+        body.record_trail(10);
+        let mut clone = body.clone();
+        clone.pos.xy = VecXy::new(5., 5.);
+        clone.vel.xy = VecXy::new(1., 0.);
+        clone.tuggers[0].world_dest = VecXy::new(-30., 0.);
+        clone.tuggers.push(center_tugger(VecXy::ZERO));
+        clone.record_trail(10);
+
+        assert_eq!((body.pos.xy, body.vel.xy), (VecXy::ZERO, VecXy::ZERO));
+        assert_eq!(body.tuggers.len(), 1);
+        assert_eq!(body.tuggers[0].world_dest, VecXy::new(30., 0.));
+        assert_eq!(body.trail, [VecXy::ZERO]);
+        assert_eq!(clone.trail, [VecXy::ZERO, VecXy::new(5., 5.)]);
+    }
+
+    #[test]
+    fn spanned_bodies_fill_their_drawn_rectangle() {
+        let start = VecXy::new(10., 20.);
+        // dragging down and right, then up and left
+        for end in [VecXy::new(70., 45.), VecXy::new(-30., -5.)] {
+            let body = Body::new_spanning(start, end);
+            let half = body.aabb_half_extents();
+            assert_eq!(body.scale, (end - start).abs());
+            assert_eq!([body.pos.xy - half, body.pos.xy + half], [start.min(end), start.max(end)]);
+        }
+    }
+
+    #[test]
+    fn handle_arcs_sweep_from_orientation_to_handle() {
+        let mut body = square(VecXy::new(5., -5.), 20.);
+        body.pos.angle = 0.5;
+        let handle = VecLa { length: 8., angle: -1.2 };
+        let [start, end] = body.handle_arc_angles(handle);
+        assert_eq!(start, body.pos.angle);
+        // the arc ends right at the handle
+        let arc_end = body.pos.xy + VecLa { length: handle.length, angle: end }.to_xy();
+        assert!(arc_end.distance(body.absolute_handle(handle)) < 1e-5);
+    }
+
+    #[test]
+    fn swapping_dests_spares_the_first_body_unless_swapping_all() {
+        let tugged = |x| {
+            let mut body = square(VecXy::new(x, 0.), 20.);
+            body.tuggers.push(center_tugger(VecXy::new(x, 100.)));
+            body
+        };
+        let mut world = World::new(vec![tugged(10.), tugged(50.)], VecXy::ZERO);
+        let dests = |world: &World| -> Vec<VecXy> {
+            world.bodies.iter().map(|body| body.tuggers[0].world_dest).collect()
+        };
+        world.swap_dests(false);
+        assert_eq!(dests(&world), [VecXy::new(10., 100.), VecXy::new(100., 50.)]);
+        world.swap_dests(true);
+        assert_eq!(dests(&world), [VecXy::new(100., 10.), VecXy::new(50., 100.)]);
+    }
+
+    #[test]
+    fn rotating_steps_the_angle_and_stops_spin() {
+        let mut body = square(VecXy::new(3., 4.), 20.);
+        body.pos.angle = 0.25;
+        body.vel = FieldScalars { xy: VecXy::new(1., 0.), angle: 0.3 };
+        body.rotate_by(Body::ROTATE_STEP);
+        assert_eq!(body.pos.angle, 0.25 + std::f32::consts::PI / 36.);
+        assert_eq!((body.pos.xy, body.vel.xy, body.vel.angle), (VecXy::new(3., 4.), VecXy::X, 0.));
+    }
+
+    #[test]
+    fn rope_colors_are_distinct_until_they_wrap() {
+        let n = Tugger::PALETTE.len();
+        let colors: Vec<[f32; 4]> = (0..2 * n).map(Tugger::palette_color).collect();
+        for (i, a) in colors[..n].iter().enumerate() {
+            assert!(colors[i + 1..n].iter().all(|b| a != b));
+        }
+        assert_eq!(colors[..n], colors[n..]);
+    }
+
+    #[test]
+    fn drawn_acceleration_sums_tugs_and_fields() {
+        let mut body = square(VecXy::ZERO, 20.);
+        let tugger = Tugger {
+            relative_body_handle_xy: VecLa { length: 5., angle: 1. },
+            ..center_tugger(VecXy::new(30., -10.))
+        };
+        body.tuggers.push(tugger.clone());
+        let gravity = Gravity { acc: VecXy::new(0., 0.1) };
+        let handle = body.xy_relative_handle(tugger.relative_body_handle_xy);
+        let tug = body.tug_acc(handle, body.tug_force(&tugger).length_capped(body.acc_scalar));
+        body.step(1., &[&gravity], Integrator::SemiImplicitEuler);
+        assert!(body.acc.xy.distance(tug.xy + gravity.acc) < 1e-6);
+        assert!((body.acc.angle - tug.angle).abs() < 1e-6);
+    }
+
+    #[test]
+    fn only_bodies_minding_gravity_fall() {
+        let mut floating = square(VecXy::new(-50., 0.), 20.);
+        floating.ignore_gravity = true;
+        let falling = square(VecXy::new(50., 0.), 20.);
+        let mut world = World::new(vec![floating, falling], VecXy::new(0., 0.1));
+        world.simulate(10);
+        assert_eq!(world.bodies[0].vel.xy, VecXy::ZERO);
+        assert_eq!(world.bodies[0].pos.xy, VecXy::new(-50., 0.));
+        assert!((world.bodies[1].vel.xy.y - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn wireframes_draw_outline_meshes() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.scale = VecXy::new(30., 20.);
+        assert_eq!(body.mesh(false), (MeshKind::Rect, VecXy::new(30., 20.)));
+        assert_eq!(body.mesh(true), (MeshKind::RectWire, VecXy::new(30., 20.)));
+        body.shape = Shape::Circle;
+        assert_eq!(body.mesh(false), (MeshKind::Circle, VecXy::splat(20.)));
+        assert_eq!(body.mesh(true), (MeshKind::CircleWire, VecXy::splat(20.)));
+    }
+
+    #[test]
+    fn bodies_are_labeled_with_their_index_at_their_center() {
+        let bodies: Vec<Body> =
+            (0..12).map(|i| square(VecXy::new(i as f32 * 30., 5.), 20.)).collect();
+        let mut world = World::new(bodies, VecXy::ZERO);
+        world.remove_body(world.bodies[3].id);
+        for (index, (center, text)) in world.labels().enumerate() {
+            assert_eq!(center, world.bodies[index].pos.xy);
+            assert_eq!(text, index.to_string());
+        }
+        assert_eq!(world.labels().last().unwrap().1, "10");
+    }
+
+    #[test]
+    fn bounds_outline_matches_the_ticked_bounds() {
+        let mut world = World::new(vec![square(VecXy::ZERO, 20.)], VecXy::ZERO);
+        assert_eq!(world.bounds_rect(), None);
+        let [min, size] = [VecXy::new(10., 20.), VecXy::new(800., 600.)];
+        world.set_bounds_rect(min, size);
+        world.tick(&[]);
+        assert_eq!(world.bounds, [min, VecXy::new(810., 620.)]);
+        assert_eq!(world.bounds_rect(), Some([min, size]));
+    }
+
+    #[test]
+    fn simulate_is_deterministic() {
+        let run = || {
+            let mut falling = square(VecXy::new(0., -40.), 20.);
+            falling.vel.angle = 0.05;
+            let mut world =
+                World::new(vec![falling, square(VecXy::ZERO, 30.)], VecXy::new(0., 0.1));
+            world.jitter = true;
+            world.simulate(100);
+            world.bodies.iter().map(|body| body.pos.clone()).collect::<Vec<_>>()
+        };
+        let [a, b] = [run(), run()];
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!((a.xy, a.angle), (b.xy, b.angle));
+        }
+    }
+
+    #[test]
+    fn step_hooks_run_around_integration() {
+        let mut world = World::new(vec![square(VecXy::ZERO, 20.)], VecXy::new(0., 1.));
+        // the pre step hook's push is integrated within the same tick
+        world.set_pre_step(Some(Box::new(|world: &mut World| world.bodies[0].vel.xy.x = 1.)));
+        world.set_post_step(Some(Box::new(|world: &mut World| world.gravity = VecXy::ZERO)));
+        world.tick(&[]);
+        assert_eq!(world.bodies[0].pos.xy, VecXy::new(1., 1.));
+        assert_eq!(world.gravity, VecXy::ZERO);
+    }
+
+    #[test]
+    fn step_sums_force_fields() {
+        let mut body = square(VecXy::new(30., 40.), 20.);
+        let gravity = Gravity { acc: VecXy::new(0., 0.1) };
+        let attractor = PointGravity { center: VecXy::ZERO, strength: 500., min_distance: 1. };
+        let expected = gravity.accel_at(&body) + attractor.accel_at(&body);
+        body.step(1., &[&gravity, &attractor], Integrator::SemiImplicitEuler);
+        assert!(body.vel.xy.distance(expected.xy) < 1e-6);
+        assert_eq!(body.vel.angle, expected.angle);
+
+        body.ignore_gravity = true;
+        assert_eq!(gravity.accel_at(&body).xy, VecXy::ZERO);
+    }
+
+    #[test]
+    fn viscous_region_slows_bodies_within_it() {
+        let region = ViscousRegion { min: VecXy::ZERO, max: VecXy::splat(10.), viscosity: 0.5 };
+        let mut body = square(VecXy::splat(5.), 2.);
+        body.vel = FieldScalars { xy: VecXy::new(2., 0.), angle: 0.2 };
+        let acc = region.accel_at(&body);
+        assert_eq!((acc.xy, acc.angle), (VecXy::new(-1., 0.), -0.1));
+        body.pos.xy = VecXy::new(11., 5.);
+        assert_eq!(region.accel_at(&body).xy, VecXy::ZERO);
+    }
+
+    /// `resolve_collision` of the given bodies if they collide, returning them
+    fn collide(mut a: Body, mut b: Body) -> [Body; 2] {
+        let collision = a.collides_with(&b).expect("bodies should overlap");
+        a.resolve_collision(&mut b, &collision);
+        [a, b]
+    }
+
+    #[test]
+    fn overlapping_unit_rects_report_their_penetration_depth() {
+        let unit = |xy: VecXy, angle: f32| {
+            let mut body = square(xy, 1.);
+            body.scale = VecXy::ONE;
+            body.pos.angle = angle;
+            body
+        };
+        let collision =
+            unit(VecXy::ZERO, 0.).collides_with(&unit(VecXy::new(0.7, 0.2), 0.)).unwrap();
+        assert!(collision.mtv.distance(VecXy::new(0.3, 0.)) < 1e-6);
+        assert_eq!(collision.normal, VecXy::X);
+
+        // a diamond's corner poking into the side of a square
+        let diamond = unit(VecXy::new(1.2, 0.), std::f32::consts::FRAC_PI_4);
+        let collision = unit(VecXy::ZERO, 0.).collides_with(&diamond).unwrap();
+        let depth = 0.5 + std::f32::consts::FRAC_1_SQRT_2 - 1.2;
+        assert!(collision.mtv.distance(VecXy::new(depth, 0.)) < 1e-5);
+
+        assert!(unit(VecXy::ZERO, 0.).collides_with(&unit(VecXy::new(1.1, 0.), 0.)).is_none());
+    }
+
+    #[test]
+    fn collisions_conserve_momentum() {
+        let mut heavy = square(VecXy::ZERO, 30.);
+        heavy.vel.xy = VecXy::new(0.5, 0.2);
+        let mut light = square(VecXy::new(19., 3.), 10.);
+        light.vel.xy = VecXy::new(-2., 0.);
+        let before = heavy.momentum() + light.momentum();
+        let [heavy, light] = collide(heavy, light);
+        let after = heavy.momentum() + light.momentum();
+        assert!(before.distance(after) < 1e-6 * before.length());
+        // the light body takes most of the change
+        assert!((light.vel.xy.x + 2.).abs() > (heavy.vel.xy.x - 0.5).abs());
+    }
+
+    #[test]
+    fn static_bodies_collide_without_moving() {
+        let mut a = square(VecXy::ZERO, 20.);
+        let mut b = square(VecXy::new(15., 0.), 20.);
+        a.static_body = true;
+        b.static_body = true;
+        let [a, b] = collide(a, b);
+        assert_eq!((a.pos.xy, b.pos.xy), (VecXy::ZERO, VecXy::new(15., 0.)));
+        assert!(a.vel.is_finite() && b.vel.is_finite());
+    }
+
+    #[test]
+    fn frozen_bodies_act_infinitely_heavy() {
+        let mut ball = square(VecXy::ZERO, 20.);
+        ball.shape = Shape::Circle;
+        ball.restitution = 1.;
+        ball.vel.xy = VecXy::new(1., 0.);
+        let mut frozen = ball.clone();
+        frozen.pos.xy = VecXy::new(19., 0.);
+        frozen.vel.xy = VecXy::ZERO;
+        frozen.static_body = true;
+        let [ball, mut frozen] = collide(ball, frozen);
+        assert!(ball.vel.xy.distance(VecXy::new(-1., 0.)) < 1e-6);
+        assert_eq!(ball.vel.angle, 0.);
+        assert_eq!(frozen.pos.xy, VecXy::new(19., 0.));
+
+        frozen.apply_impulse(VecXy::new(25., 5.), VecXy::new(-3., 1.));
+        assert_eq!((frozen.vel.xy, frozen.vel.angle), (VecXy::ZERO, 0.));
+    }
+
+    #[test]
+    fn tuggers_anchor_to_static_bodies() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.tuggers.push(Tugger {
+            to_body: Some(TugTarget {
+                body: BodyHandle(1),
+                handle: VecLa { length: 0., angle: 0. },
+            }),
+            ..center_tugger(VecXy::ZERO)
+        });
+        let mut anchor = square(VecXy::new(100., 0.), 20.);
+        anchor.id = BodyHandle(1);
+        anchor.static_body = true;
+        let mut bodies = [body, anchor];
+        tug_between_bodies(&mut bodies, 1.);
+        assert_eq!((bodies[1].vel.xy, bodies[1].vel.angle), (VecXy::ZERO, 0.));
+        assert_eq!(bodies[0].tuggers[0].world_dest, VecXy::new(100., 0.));
+    }
+
+    #[test]
+    fn equal_rects_swap_velocities_head_on() {
+        let mut a = square(VecXy::ZERO, 20.);
+        a.restitution = 1.;
+        a.vel.xy = VecXy::new(1., 0.);
+        let mut b = a.clone();
+        b.pos.xy = VecXy::new(19., 0.);
+        b.vel.xy = VecXy::new(-1., 0.);
+        let [a, b] = collide(a, b);
+        assert!(a.vel.xy.distance(VecXy::new(-1., 0.)) < 1e-6);
+        assert!(b.vel.xy.distance(VecXy::new(1., 0.)) < 1e-6);
+        assert!(a.vel.angle.abs() < 1e-6 && b.vel.angle.abs() < 1e-6);
+    }
+
+    #[test]
+    fn elastic_collisions_conserve_kinetic_energy() {
+        let mut a = square(VecXy::ZERO, 20.);
+        a.restitution = 1.;
+        a.vel.xy = VecXy::new(1.5, 0.);
+        let mut b = a.clone();
+        b.pos.xy = VecXy::new(19., 0.);
+        b.vel.xy = VecXy::new(-1.5, 0.);
+        let before = a.kinetic_energy() + b.kinetic_energy();
+        let [a, b] = collide(a, b);
+        assert!(a.vel.xy.distance(VecXy::new(-1.5, 0.)) < 1e-6);
+        assert!(b.vel.xy.distance(VecXy::new(1.5, 0.)) < 1e-6);
+        let after = a.kinetic_energy() + b.kinetic_energy();
+        assert!((before - after).abs() < 1e-5 * before);
+
+        // off center, some of it turns into spin
+        let mut c = a.clone();
+        c.pos.xy = VecXy::new(-15., 12.);
+        c.vel.xy = VecXy::new(2., 0.);
+        let before = a.kinetic_energy() + c.kinetic_energy();
+        let [a, c] = collide(c, a);
+        let after = a.kinetic_energy() + c.kinetic_energy();
+        assert!(a.vel.angle != 0.);
+        assert!((before - after).abs() < 1e-5 * before);
+    }
+
+    #[test]
+    fn collision_events_report_normals_from_a_to_b() {
+        for (b_x, normal_x) in [(19., 1.), (-19., -1.)] {
+            let mut world = World::new(
+                vec![square(VecXy::ZERO, 20.), square(VecXy::new(b_x, 0.), 20.)],
+                VecXy::ZERO,
+            );
+            world.tick(&[]);
+            let [PhysicsEvent::Collision { a, b, normal, point }] = world.events[..] else {
+                panic!("expected a single collision, got {:?}", world.events);
+            };
+            assert_eq!((a, b), (0, 1));
+            assert!(normal.distance(VecXy::new(normal_x, 0.)) < 1e-6);
+            assert!(point.distance(VecXy::new(b_x * 0.5, 0.)) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn corner_contacts_lie_at_the_corner() {
+        // a diamond poking its corner into a square's left edge
+        let a = square(VecXy::ZERO, 20.);
+        let mut b = square(VecXy::new(23., 4.), 20.);
+        b.pos.angle = std::f32::consts::FRAC_PI_4;
+        let collision = a.collides_with(&b).expect("bodies should overlap");
+        let corner = 23. - 10. * std::f32::consts::SQRT_2;
+        let depth = 10. - corner;
+        assert!((collision.mtv - VecXy::new(depth, 0.)).length() < 1e-4);
+        assert!(collision.point.distance(VecXy::new(corner + depth * 0.5, 4.)) < 1e-4);
+    }
+
+    #[test]
+    fn clamping_stops_bodies_at_the_bounds() {
+        let mut body = square(VecXy::new(-5., 50.), 20.);
+        body.vel.xy = VecXy::new(-3., 2.);
+        body.clamp_within(VecXy::ZERO, VecXy::splat(100.));
+        assert_eq!(body.pos.xy, VecXy::new(10., 50.));
+        assert_eq!(body.vel.xy, VecXy::new(0., 2.));
+        // inward velocity is kept
+        body.pos.xy.x = 95.;
+        body.vel.xy.x = -1.;
+        body.clamp_within(VecXy::ZERO, VecXy::splat(100.));
+        assert_eq!(body.pos.xy, VecXy::new(90., 50.));
+        assert_eq!(body.vel.xy, VecXy::new(-1., 2.));
+    }
+
+    #[test]
+    fn walls_bounce_bodies_back_inside() {
+        let mut world = World::new(vec![square(VecXy::new(90., 50.), 20.)], VecXy::ZERO);
+        world.boundary_mode = BoundaryMode::Walls;
+        world.bounds = [VecXy::ZERO, VecXy::splat(100.)];
+        world.bodies[0].restitution = 0.5;
+        world.bodies[0].vel.xy = VecXy::new(4., 0.);
+        world.tick(&[]);
+        let body = &world.bodies[0];
+        assert_eq!(body.pos.xy, VecXy::new(90., 50.));
+        assert_eq!(body.vel.xy, VecXy::new(-2., 0.));
+        world.simulate(100);
+        assert!(world.bodies[0].pos.xy.x >= 10.);
+    }
+
+    /// Tugger at my center toward `world_dest`
+    fn center_tugger(world_dest: VecXy) -> Tugger {
+        Tugger {
+            relative_body_handle_xy: VecLa { length: 0., angle: 0. },
+            world_dest,
+            stiffness: Tugger::DEFAULT_STIFFNESS,
+            damping: Tugger::DEFAULT_DAMPING,
+            break_force: None,
+            to_body: None,
+            work: 0.,
+        }
+    }
+
+    #[test]
+    fn tugger_work_is_force_times_displacement() {
+        // far enough for the force to stay capped at the acceleration scalar
+        let mut body = square(VecXy::ZERO, 20.);
+        body.tuggers.push(center_tugger(VecXy::new(1e4, 0.)));
+        for _ in 0..10 {
+            body.step(1., &[], Integrator::default());
+        }
+        let expected = body.mass() * body.acc_scalar * body.pos.xy.x;
+        assert!((body.tuggers[0].work - expected).abs() < 1e-4 * expected);
+    }
+
+    #[test]
+    fn tugger_work_includes_the_reaction_on_its_target() {
+        let mut anchor = square(VecXy::ZERO, 20.);
+        anchor.static_body = true;
+        let handle = VecLa { length: 0., angle: 0. };
+        anchor.tuggers.push(Tugger {
+            to_body: Some(TugTarget { body: BodyHandle(1), handle }),
+            ..center_tugger(VecXy::ZERO)
+        });
+        let mut target = square(VecXy::new(100., 0.), 20.);
+        target.id = BodyHandle(1);
+        target.vel.xy = VecXy::new(1., 0.);
+        let mut bodies = [anchor, target];
+        tug_between_bodies(&mut bodies, 1.);
+        // the capped pull back on the receding target
+        let expected = -bodies[0].acc_scalar * bodies[0].mass();
+        assert!((bodies[0].tuggers[0].work - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn handles_survive_removing_earlier_bodies() {
+        let mut world = World::new(Vec::new(), VecXy::ZERO);
+        let first = world.add_body(square(VecXy::new(-100., 0.), 20.));
+        let target = world.add_body(square(VecXy::new(100., 0.), 20.));
+        let mut body = square(VecXy::ZERO, 20.);
+        let handle = VecLa { length: 0., angle: 0. };
+        body.tuggers.push(Tugger {
+            to_body: Some(TugTarget { body: target, handle }),
+            ..center_tugger(VecXy::ZERO)
+        });
+        let tugging = world.add_body(body);
+
+        assert!(world.remove_body(first).is_some());
+        assert!(world.get(first).is_none());
+        assert_eq!(world.get(target).unwrap().pos.xy, VecXy::new(100., 0.));
+        world.get_mut(target).unwrap().pos.xy = VecXy::new(50., 0.);
+        tug_between_bodies(&mut world.bodies, 1.);
+        assert_eq!(world.get(tugging).unwrap().tuggers[0].world_dest, VecXy::new(50., 0.));
+        // a new body never takes over a removed one's handle
+        assert_ne!(world.add_body(square(VecXy::ZERO, 1.)), first);
+    }
+
+    #[test]
+    fn total_momentum_excludes_static_bodies() {
+        let mut platform = square(VecXy::new(0., 100.), 50.);
+        platform.static_body = true;
+        platform.vel.xy = VecXy::new(5., 0.);
+        let mut a = square(VecXy::ZERO, 20.);
+        a.vel.xy = VecXy::new(1., 0.);
+        let b = square(VecXy::new(14., 3.), 10.);
+        let mut world = World::new(vec![a, b, platform], VecXy::ZERO);
+        let before = world.total_linear_momentum();
+        assert_eq!(before, world.bodies[0].momentum());
+        assert!(world.total_kinetic_energy() < world.bodies[2].kinetic_energy());
+
+        // an internal impulse exchanged between the dynamic bodies
+        let events = resolve_collision_pairs(&mut world.bodies, &[[0, 1]]);
+        assert_eq!(events.len(), 1);
+        assert!(world.total_linear_momentum().distance(before) < 1e-6);
+    }
+
+    #[test]
+    fn clicking_a_stack_selects_its_topmost_body() {
+        let bodies = vec![
+            square(VecXy::ZERO, 60.),
+            square(VecXy::new(5., 0.), 40.),
+            square(VecXy::new(10., 0.), 20.),
+        ];
+        let mut world = World::new(bodies, VecXy::ZERO);
+        assert_eq!(world.body_at(VecXy::new(10., 0.)), Some(2));
+        assert_eq!(world.body_at(VecXy::new(-10., 0.)), Some(1));
+        assert_eq!(world.body_at(VecXy::new(-25., 0.)), Some(0));
+        assert_eq!(world.body_at(VecXy::new(-35., 0.)), None);
+
+        // grabbing never reaches beyond the grabbed body
+        world.bodies[2].max_tug_handle_distance = 2.;
+        let handle = world.bodies[2].grab_handle(VecXy::new(15., 0.));
+        assert_eq!(handle.length, 2.);
+    }
+
+    #[test]
+    fn saved_scenes_reload_bit_for_bit() {
+        let scene = Scene::fallback(1.);
+        let path = std::env::temp_dir().join(format!("scene-{}.json", std::process::id()));
+        scene.save(&path).unwrap();
+        let reloaded = Scene::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let reloaded = reloaded.unwrap();
+
+        let bits =
+            |scalars: &FieldScalars| [scalars.xy.x, scalars.xy.y, scalars.angle].map(f32::to_bits);
+        assert_eq!(reloaded.gravity, scene.gravity);
+        assert_eq!(reloaded.bodies.len(), scene.bodies.len());
+        for (body, was) in reloaded.bodies.iter().zip(&scene.bodies) {
+            assert_eq!(bits(&body.pos), bits(&was.pos));
+            assert_eq!(bits(&body.vel), bits(&was.vel));
+        }
+    }
+
+    #[test]
+    fn repro_round_trips() {
+        let mut a = square(VecXy::ZERO, 20.);
+        a.vel.xy = VecXy::new(2., 0.);
+        let b = square(VecXy::new(30., 1.), 10.);
+        let mut world = World::new(vec![a, b], VecXy::new(0., 0.1));
+        world.jitter = true;
+        world.integrator = Integrator::Verlet;
+        // JSON has no infinities, so the default unbounded world wouldn't survive
+        world.boundary_mode = BoundaryMode::Walls;
+        world.bounds = [VecXy::splat(-100.), VecXy::splat(100.)];
+        world.simulate(25);
+
+        let json = serde_json::to_string(&world.repro()).unwrap();
+        let mut reloaded = World::new(Vec::new(), VecXy::ZERO);
+        reloaded.restore(serde_json::from_str(&json).unwrap());
+        assert_eq!(reloaded.ticks, 25);
+        assert_eq!(reloaded.rng, world.rng);
+        assert_eq!(reloaded.config(), world.config());
+
+        world.simulate(25);
+        reloaded.simulate(25);
+        assert_eq!(reloaded.ticks, 50);
+        for (body, replayed) in world.bodies.iter().zip(&reloaded.bodies) {
+            assert_eq!(body.pos.xy, replayed.pos.xy);
+            assert_eq!(body.pos.angle, replayed.pos.angle);
+        }
+    }
+
+    #[test]
+    fn shaking_is_reproducible() {
+        let shaken = || {
+            let mut wall = square(VecXy::new(50., 0.), 20.);
+            wall.static_body = true;
+            let mut world = World::new(vec![square(VecXy::ZERO, 20.), wall], VecXy::ZERO);
+            world.shake();
+            world.bodies.iter().map(|body| body.vel.clone()).collect::<Vec<_>>()
+        };
+        let [first, second] = [shaken(), shaken()];
+        assert!(first[0].xy != VecXy::ZERO && first[0].xy.length() < World::SHAKE_STRENGTH);
+        assert_eq!((first[0].xy, first[0].angle), (second[0].xy, second[0].angle));
+        assert_eq!((first[1].xy, first[1].angle), (VecXy::ZERO, 0.));
+    }
+
+    #[test]
+    fn positive_y_velocity_moves_up_on_screen() {
+        let screen_center = VecXy::new(400., 300.);
+        for y_up in [false, true] {
+            let camera = Camera { offset: VecXy::new(30., -20.), zoom: 2., y_up };
+            let mut body = square(VecXy::new(100., 100.), 20.);
+            body.vel.xy = VecXy::new(0., 1.);
+            let before = camera.world_to_screen(screen_center, body.pos.xy);
+            body.step(1., &[], Integrator::default());
+            let after = camera.world_to_screen(screen_center, body.pos.xy);
+            assert_eq!(after.y < before.y, y_up);
+            let round_trip = camera.screen_to_world(screen_center, after);
+            assert!(round_trip.distance(body.pos.xy) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn world_lengths_shrink_with_zoom() {
+        let screen_center = VecXy::new(400., 300.);
+        let camera = Camera { zoom: 4., ..Camera::default() };
+        let length = camera.world_length(1.);
+        let [a, b] = [VecXy::new(10., 10.), VecXy::new(10. + length, 10.)]
+            .map(|xy| camera.world_to_screen(screen_center, xy));
+        assert!((a.distance(b) - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn adding_tuggers_beyond_the_limit_evicts_the_oldest() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.max_tuggers = Some(2);
+        body.mouse_tugger = Some(center_tugger(VecXy::ZERO));
+        for x in [1., 2., 3.] {
+            body.add_tugger(center_tugger(VecXy::new(x, 0.)));
+        }
+        let dests: Vec<f32> = body.tuggers.iter().map(|tugger| tugger.world_dest.x).collect();
+        assert_eq!(dests, [2., 3.]);
+        assert!(body.mouse_tugger.is_some());
+    }
+
+    #[test]
+    fn fitting_the_camera_frames_all_bodies() {
+        let bodies =
+            vec![square(VecXy::new(2000., -500.), 100.), square(VecXy::new(3000., 0.), 50.)];
+        let world = World::new(bodies, VecXy::ZERO);
+        let [screen_center, screen_size] = [VecXy::new(400., 300.), VecXy::new(800., 600.)];
+        let mut camera = Camera::default();
+        camera.fit(screen_center, screen_size, world.aabb().unwrap(), 0.1);
+        for body in &world.bodies {
+            let half = body.aabb_half_extents();
+            for corner in [body.pos.xy - half, body.pos.xy + half] {
+                let on_screen = camera.world_to_screen(screen_center, corner);
+                assert_eq!(on_screen.clamp(VecXy::ZERO, screen_size), on_screen);
+            }
+        }
+        // filling the screen's width, the tighter fit
+        let [min, max] = world.aabb().unwrap();
+        assert!((camera.zoom - 720. / (max - min).x).abs() < 1e-6);
+    }
+
+    #[test]
+    fn toggling_shape_keeps_motion_and_bounds_the_rectangle() {
+        let mut body = Body::new_spanning(VecXy::ZERO, VecXy::new(30., 40.));
+        body.vel = FieldScalars { xy: VecXy::new(1., -2.), angle: 0.1 };
+        body.tuggers.push(center_tugger(VecXy::ZERO));
+        body.toggle_shape();
+        assert_eq!(body.shape, Shape::Circle);
+        assert_eq!(body.radius(), 25.);
+        assert_eq!(body.max_tug_handle_distance, 25.);
+        assert_eq!(
+            (body.pos.xy, body.vel.xy, body.vel.angle),
+            (VecXy::new(15., 20.), VecXy::new(1., -2.), 0.1)
+        );
+        assert_eq!(body.tuggers.len(), 1);
+        body.toggle_shape();
+        assert_eq!((body.shape, body.scale), (Shape::Rect, VecXy::new(30., 40.)));
+        assert_eq!(body.max_tug_handle_distance, 25.);
+    }
+
+    #[test]
+    fn sleep_fade_progresses_by_frame() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.asleep = true;
+        let mut fades = Vec::new();
+        for frame in 0..12 {
+            if frame == 9 {
+                body.wake();
+            }
+            body.update_sleep_fade();
+            fades.push(body.sleep_fade);
+        }
+        assert_eq!(
+            fades,
+            [0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875, 1., 1., 0.875, 0.75, 0.625]
+        );
+    }
+
+    #[test]
+    fn set_velocity_at_round_trips() {
+        let mut body = square(VecXy::ZERO, 20.);
+        body.pos.angle = 0.7;
+        body.vel.angle = 0.3;
+        let handle = VecLa { length: 8., angle: 1.2 };
+        body.set_velocity_at(handle, VecXy::new(2., -1.));
+        assert!(body.velocity_at(handle).distance(VecXy::new(2., -1.)) < 1e-5);
+        assert_eq!(body.vel.angle, 0.3);
+    }
+}
+
+#[cfg(test)]
+mod tug_acc_fuzz {
+    use super::*;
+
+    /// Bodies of degenerate to large sizes and densities, of either shape, static or not
+    fn bodies() -> Vec<Body> {
+        let mut bodies = Vec::new();
+        for side in [0., 1e-3, 1., 50., 1e4] {
+            for density in [0., 1e-6, Body::DEFAULT_DENSITY, 10.] {
+                for shape in [Shape::Rect, Shape::Circle] {
+                    for static_body in [false, true] {
+                        let mut body = Body::new_spanning(VecXy::ZERO, VecXy::ZERO);
+                        body.scale = VecXy::new(side, side * 0.5);
+                        body.max_tug_handle_distance = body.bounding_radius();
+                        body.density = density;
+                        body.shape = shape;
+                        body.static_body = static_body;
+                        bodies.push(body);
+                    }
+                }
+            }
+        }
+        bodies
+    }
+
+    #[test]
+    fn tug_acc_stays_finite() {
+        let mut rng = Rng::new(241);
+        for body in bodies() {
+            let reach = body.max_tug_handle_distance;
+            let direction = rng.vec_xy(1.).normalize_or_zero();
+            let contacts =
+                [VecXy::ZERO, direction * reach, -direction * reach, rng.vec_xy(reach), VecXy::X];
+            let forces = [
+                VecXy::ZERO,
+                rng.vec_xy(1.).with_length(body.acc_scalar),
+                rng.vec_xy(body.acc_scalar),
+                VecXy::splat(f32::MIN_POSITIVE),
+            ];
+            for contact in contacts {
+                for force in forces {
+                    let acc = body.tug_acc(contact, force);
+                    assert!(
+                        acc.is_finite(),
+                        "tug_acc({:?}, {:?}) = {:?} for a {:?} of scale {:?} and density {}",
+                        contact,
+                        force,
+                        acc,
+                        body.shape,
+                        body.scale,
+                        body.density
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tug_acc_of_static_bodies_is_zero() {
+        for body in bodies().into_iter().filter(|body| body.static_body) {
+            let acc = body.tug_acc(VecXy::new(1., 2.), VecXy::new(0.1, -0.1));
+            assert_eq!((acc.xy, acc.angle), (VecXy::ZERO, 0.));
+        }
+    }
+}
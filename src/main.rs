@@ -1,249 +1,462 @@
 use ggez::{
     event::{self, quit, EventHandler, MouseButton},
-    graphics::{self, Color, DrawParam, Mesh, Transform},
-    input::keyboard::{KeyCode, KeyMods},
-    Context, ContextBuilder, GameResult,
+    graphics::{self, Color, DrawParam, Mesh, Rect, Text, Transform},
+    input::{
+        keyboard::{self, KeyCode, KeyMods},
+        mouse,
+    },
+    timer, Context, ContextBuilder, GameResult,
 };
 use glam::Vec2 as VecXy;
-
-/// 2D vector in length-angle form
-#[derive(Debug, Copy, Clone)]
-struct VecLa {
-    length: f32,
-    angle: f32,
-}
-
-/// generalization of {position, velocity, ...} of rotating 2d body
-#[derive(Default, Debug)]
-struct FieldScalars {
-    xy: VecXy,
-    angle: f32,
-}
-
-// /// Body statics for acceleration of some scalar
-// struct VelocityStatic {
-//     acc_scalar: f32,
-//     linear_friction_scalar: f32,
-//     constant_friction: f32,
-// }
-
-// /// VelocityStatic for {xy, angle}
-// struct VelocityStatics {
-//     xy: VelocityStatic,
-//     angle: VelocityStatic,
-// }
-
-struct Tugger {
-    relative_body_handle_xy: VecLa,
-    world_dest: VecXy,
-}
-
-/// A 2d shape in the game world
-struct Body {
-    // statics: VelocityStatics,
-    acc_scalar: f32,
-    rot_acc_scalar: f32,
-    pos: FieldScalars,
-    vel: FieldScalars,
-    scale: VecXy,
-    tuggers: [Option<Tugger>; 2],
-    max_tug_handle_distance: f32,
-}
+use std::{
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+use torque_on_2d_shapes::{
+    safe_div, Action, AutoPause, Body, BodyHandle, BoundaryMode, Camera, FieldScalars,
+    FixedTimestep, ForceField, Integrator, KeyBindings, MeshKind, NegIf, PhysicsEvent,
+    PointGravity, Repro, Scene, StepTimings, Tugger, VecLa, VecXyExt, ViscousRegion, Wind, World,
+};
 
 /// Game state
 struct MyGame {
     rect_mash: Mesh,
-    bodies: [Body; 2],
+    /// outline counterpart of `rect_mash`
+    rect_wire_mash: Mesh,
+    /// unit diameter circle, scaled to draw circular bodies
+    circle_mesh: Mesh,
+    /// outline counterpart of `circle_mesh`
+    circle_wire_mesh: Mesh,
+    world: World,
+    /// the world's bodies as the game started, restored by resetting
+    initial_bodies: Vec<Body>,
+    /// number of bodies classified as at rest during the latest update
+    at_rest_count: usize,
+    /// when set, left mouse drags draw new bodies instead of tugging
+    draw_mode: bool,
+    /// [start, current] world points of an in-progress body drawing drag
+    drawing: Option<[VecXy; 2]>,
+    camera: Camera,
+    /// when set, the camera frames all bodies of each loaded scene
+    fit_camera_on_load: bool,
+    /// body the camera keeps centered
+    follow: Option<BodyHandle>,
+    /// swapped with the world's gravity when toggling gravity, so it can be restored
+    stashed_gravity: VecXy,
+    /// when set, the cursor attracts every body like a point mass
+    gravity_well_active: bool,
+    /// when set, the cursor's gravity well repels instead
+    gravity_well_repels: bool,
+    /// acceleration toward the cursor at unit distance from it
+    gravity_well_strength: f32,
+    /// acceleration of every body while `wind_enabled`
+    wind: VecXy,
+    wind_enabled: bool,
+    /// when set, wind pushes bodies by their extent across it, over their mass
+    wind_by_cross_section: bool,
+    /// when set, the bottom of the bounds is filled with a viscous liquid
+    viscous_pool: bool,
+    key_bindings: KeyBindings<KeyCode>,
+    /// distance between the screen edges and the bounds
+    bounds_margin: f32,
+    /// when set, the bounds are outlined
+    draw_bounds: bool,
+    paused: bool,
+    /// set to advance a single tick while paused
+    step_once: bool,
+    timestep: FixedTimestep,
+    /// simulated seconds per real second
+    time_scale: f32,
+    /// pauses the simulation once all bodies stay at rest, until the next user interaction
+    auto_pause: AutoPause,
+    /// when set, swapping tugger destinations also affects the first body
+    swap_all: bool,
+    /// latest cursor position, toward which mouse tuggers' destinations are smoothed
+    drag_target: VecXy,
+    /// fraction of the remaining distance to `drag_target` covered by mouse tuggers per update.
+    /// 1.0 follows the cursor instantly
+    drag_smoothing: f32,
+    /// break force of mouse tuggers, which never break when unset
+    mouse_break_force: Option<f32>,
+    /// distance from a right click within which bodies are blasted away
+    explosion_radius: f32,
+    /// speed given to bodies by a right click blast at its center, falling off to zero at its radius
+    explosion_strength: f32,
+    /// topmost body under the cursor
+    hovered: Option<BodyHandle>,
+    /// body most recently grabbed with the mouse
+    selected: Option<BodyHandle>,
+    /// when set, each tugger's rope gets a distinct color by index
+    color_tuggers: bool,
+    /// when set, bodies are drawn as outlines rather than filled
+    wireframe: bool,
+    /// when set, each body leaves a fading trail of its recent positions
+    draw_trails: bool,
+    /// when set, bodies are colored from blue to red by their speed
+    speed_heatmap: bool,
+    /// speed at and beyond which bodies are drawn fully red in the speed heatmap
+    heatmap_max_speed: f32,
+    /// when set, each body is labeled with its index
+    draw_labels: bool,
+    /// when set, additional diagnostic geometry is drawn
+    debug_draw: bool,
+    /// when set, the time spent ticking each frame is summarized on stderr
+    log_step_timings: bool,
+    step_timings: StepTimings,
 }
 
-/// Utility functions for `f32` type. Workaround of orphan rule.
-trait NegIf: Sized {
-    fn neg_if(self, cond: bool) -> Self;
-    fn toward_zero_saturating(self, by: f32) -> Self;
-}
+/////////////////////////////////
 
-/// Utility functions for `VecXy` type. Workaround of orphan rule.
-trait VecXyExt: Sized {
-    fn rotated(self, angle: f32) -> Self;
-    fn split_parr_perp(self, other: Self) -> [Self; 2];
-    fn with_length(self, length: f32) -> Self;
-    fn reduce_length_saturating(self, by: f32) -> Self;
-    fn length_capped(self, at: f32) -> Self;
+/// Keys bound to each action unless configured otherwise
+fn default_key_bindings() -> KeyBindings<KeyCode> {
+    KeyBindings {
+        quit: KeyCode::Escape,
+        toggle_draw_mode: KeyCode::B,
+        cycle_boundary_mode: KeyCode::C,
+        shake: KeyCode::S,
+        toggle_swap_all: KeyCode::A,
+        toggle_ignore_gravity: KeyCode::I,
+        rotate_ccw: KeyCode::Q,
+        rotate_cw: KeyCode::E,
+        toggle_jitter: KeyCode::J,
+        toggle_rope_colors: KeyCode::T,
+        toggle_wireframe: KeyCode::W,
+        toggle_debug_draw: KeyCode::V,
+        toggle_pause: KeyCode::P,
+        rewind: KeyCode::Back,
+        swap_dests: KeyCode::Space,
+        snap_to_floor: KeyCode::F,
+        toggle_step_timings: KeyCode::L,
+        mirror_scene: KeyCode::M,
+        toggle_adaptive_timestep: KeyCode::D,
+        toggle_auto_pause: KeyCode::Z,
+        randomize_constants: KeyCode::K,
+        toggle_labels: KeyCode::Tab,
+        toggle_bounds: KeyCode::X,
+        toggle_gravity: KeyCode::G,
+        save_scene: KeyCode::F5,
+        load_scene: KeyCode::F9,
+        reset: KeyCode::R,
+        spawn_body: KeyCode::N,
+        delete_body: KeyCode::Delete,
+        toggle_speed_heatmap: KeyCode::H,
+        slow_down: KeyCode::LBracket,
+        speed_up: KeyCode::RBracket,
+        step_once: KeyCode::Period,
+        toggle_trails: KeyCode::O,
+        toggle_wind: KeyCode::U,
+        toggle_static: KeyCode::Y,
+        reset_camera: KeyCode::Home,
+        cycle_integrator: KeyCode::F2,
+        toggle_viscous_pool: KeyCode::F3,
+        flip_y_axis: KeyCode::F4,
+        pin_tugger: KeyCode::Insert,
+        toggle_shape: KeyCode::F6,
+        save_repro: KeyCode::F7,
+        load_repro: KeyCode::F8,
+    }
 }
 
-/////////////////////////////////
+impl MyGame {
+    /// Maximum relative change of body constants when randomizing them
+    const CONSTANTS_SPREAD: f32 = 0.2;
 
-impl NegIf for f32 {
-    fn neg_if(self, cond: bool) -> Self {
-        if cond {
-            -self
-        } else {
-            self
-        }
-    }
-    fn toward_zero_saturating(self, by: f32) -> Self {
-        if self >= 0. {
-            (self - by).max(0.)
-        } else {
-            (self + by).min(0.)
+    /// Factor by which the grabbed body is scaled, or else the camera zoomed, per scroll wheel tick
+    const WHEEL_SCALE_FACTOR: f32 = 1.1;
+    /// Fraction of the remaining distance to a followed body covered by the camera per update
+    const FOLLOW_SMOOTHING: f32 = 0.1;
+    /// Range of the camera zoom
+    const ZOOM_RANGE: [f32; 2] = [0.1, 10.];
+    /// Screen distance panned per second while an arrow key is held
+    const PAN_SPEED: f32 = 400.;
+    /// Width and height of bodies spawned at the cursor
+    const SPAWN_SIZE: f32 = 40.;
+    /// Fraction by which the colors of bodies are darkened once they are asleep
+    const SLEEP_DIMMING: f32 = 0.5;
+    /// Tint of the body under the cursor
+    const HOVER_COLOR: Color = Color::new(1., 1., 0.6, 1.);
+    /// Drawn length of acceleration vectors per unit of acceleration
+    const ACC_DRAW_SCALE: f32 = 100.;
+    /// Drawn length of velocity vectors per unit of velocity, capped at `MAX_VEL_DRAW_LENGTH`
+    const VEL_DRAW_SCALE: f32 = 10.;
+    const MAX_VEL_DRAW_LENGTH: f32 = 100.;
+    /// Drawn angular offset of angular velocity ticks per unit of angular velocity
+    const SPIN_DRAW_SCALE: f32 = 20.;
+    /// Number of positions kept in each body's trail while drawing trails
+    const TRAIL_LENGTH: usize = 60;
+    /// Drawn length of collision contact normals
+    const NORMAL_DRAW_LENGTH: f32 = 20.;
+    /// Width and height of each dot of a motion trail
+    const TRAIL_DOT_SIZE: f32 = 3.;
+    /// Length of the arrow indicating the wind direction
+    const WIND_INDICATOR_LENGTH: f32 = 60.;
+    /// Angle spanned by each line segment of drawn arcs
+    const ARC_STEP_ANGLE: f32 = 0.1;
+
+    /// Resume from any automatic pause and wake all bodies, in response to user interaction
+    fn wake(&mut self) {
+        self.auto_pause.resume();
+        for body in self.world.bodies.iter_mut() {
+            body.wake();
         }
     }
-}
-impl VecXyExt for VecXy {
-    fn rotated(self, angle: f32) -> Self {
-        let [sa, ca] = [angle.sin(), angle.cos()];
-        let [x, y] = self.to_array();
-        Self::new(x * ca - y * sa, x * sa + y * ca)
+
+    /// Handle of the topmost body at `world_xy`, if any
+    fn body_at(&self, world_xy: VecXy) -> Option<BodyHandle> {
+        self.world.body_at(world_xy).map(|index| self.world.bodies[index].id)
     }
-    fn split_parr_perp(self, other: Self) -> [Self; 2] {
-        let parr = self.project_onto(other);
-        let perp = self - parr;
-        [parr, perp]
+
+    /// Remove the topmost body at `world_xy`, if any
+    fn delete_body_at(&mut self, world_xy: VecXy) {
+        if let Some(handle) = self.body_at(world_xy) {
+            self.world.remove_body(handle);
+        }
     }
-    fn with_length(self, length: f32) -> Self {
-        self.normalize_or_zero() * length
+
+    /// Multiply the time scale by `factor`, within `TIME_SCALE_RANGE`
+    fn scale_time(&mut self, factor: f32) {
+        let [min, max] = Self::TIME_SCALE_RANGE;
+        self.time_scale = (self.time_scale * factor).clamp(min, max);
     }
-    fn reduce_length_saturating(self, by: f32) -> Self {
-        self.with_length(self.length().toward_zero_saturating(by))
+
+    /// Push every body near `world_xy` away from it once, harder the nearer it is
+    fn explode_at(&mut self, world_xy: VecXy) {
+        for body in self.world.bodies.iter_mut() {
+            let away = body.pos.xy - world_xy;
+            let distance = away.length();
+            if self.explosion_radius <= distance || distance == 0. {
+                continue;
+            }
+            let direction = away / distance;
+            let strength = self.explosion_strength * (1. - distance / self.explosion_radius);
+            // the blast lands on the side facing it
+            body.apply_impulse(body.support(-direction), direction * strength);
+        }
     }
-    fn length_capped(self, cap: f32) -> Self {
-        let now = self.length();
-        if cap < now {
-            self * cap / now
-        } else {
-            self
+
+    /// Restore the bodies as the game started, releasing any mouse drag
+    fn reset(&mut self) {
+        self.world.set_bodies(self.initial_bodies.clone());
+        for body in self.world.bodies.iter_mut() {
+            body.mouse_tugger = None;
         }
+        self.selected = None;
+        self.follow = None;
     }
-}
 
-impl VecLa {
-    fn to_xy(self) -> VecXy {
-        VecXy::new(self.length, 0.).rotated(self.angle)
+    /// World area shown on screen, given the screen coordinates `view`.
+    /// Flipped, i.e., with a negative height, while y points up.
+    fn world_view(&self, view: Rect) -> Rect {
+        let center = VecXy::from(view.center());
+        let [min, max] =
+            [VecXy::new(view.left(), view.top()), VecXy::new(view.right(), view.bottom())]
+                .map(|corner| self.camera.screen_to_world(center, corner));
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
     }
-    fn from_xy(xy: VecXy) -> Self {
-        Self { length: xy.length(), angle: xy.y.atan2(xy.x) }
+    /// Multiply the camera zoom by `factor` within `ZOOM_RANGE`, keeping the world point under
+    /// the given screen point in place
+    fn zoom_at(&mut self, ctx: &Context, screen_xy: VecXy, factor: f32) {
+        let before = self.screen_to_world(ctx, screen_xy);
+        let [min, max] = Self::ZOOM_RANGE;
+        self.camera.zoom = (self.camera.zoom * factor).clamp(min, max);
+        self.camera.offset += before - self.screen_to_world(ctx, screen_xy);
     }
-}
-
-impl FieldScalars {
-    fn add(mut self, other: Self) -> Self {
-        self.add_from(&other);
-        self
+    /// World area bodies are kept within, i.e., the screen minus `bounds_margin`
+    fn bounds(&self, ctx: &Context) -> Rect {
+        let view = graphics::screen_coordinates(ctx);
+        let margin = self.bounds_margin;
+        Rect::new(view.x + margin, view.y + margin, view.w - 2. * margin, view.h - 2. * margin)
     }
-    fn add_from(&mut self, other: &Self) {
-        self.xy += other.xy;
-        self.angle += other.angle;
+    /// World position shown at the given point on screen
+    fn screen_to_world(&self, ctx: &Context, screen_xy: VecXy) -> VecXy {
+        let center = graphics::screen_coordinates(ctx).center().into();
+        self.camera.screen_to_world(center, screen_xy)
+    }
+    /// Point on screen showing the given world position
+    fn world_to_screen(&self, ctx: &Context, world_xy: VecXy) -> VecXy {
+        let center = graphics::screen_coordinates(ctx).center().into();
+        self.camera.world_to_screen(center, world_xy)
     }
-}
 
-impl Body {
-    fn xy_relative_handle(&self, mut body_handle: VecLa) -> VecXy {
-        body_handle.angle += self.pos.angle;
-        body_handle.to_xy()
+    fn selected_body_mut(&mut self) -> Option<&mut Body> {
+        self.world.get_mut(self.selected?)
     }
-    fn absolute_handle(&self, body_handle: VecLa) -> VecXy {
-        self.xy_relative_handle(body_handle) + self.pos.xy
+
+    /// Distance from the cursor within which its gravity well stops growing stronger
+    const GRAVITY_WELL_MIN_DISTANCE: f32 = 20.;
+
+    /// Fraction of the bounds' height filled by the viscous pool
+    const POOL_DEPTH: f32 = 1. / 3.;
+    const POOL_VISCOSITY: f32 = 0.05;
+
+    /// World area filled by the viscous pool, at the bottom of `bounds`
+    fn pool(&self, bounds: Rect) -> Rect {
+        let depth = bounds.h * Self::POOL_DEPTH;
+        let top = if self.camera.y_up { bounds.top() } else { bounds.bottom() - depth };
+        Rect::new(bounds.x, top, bounds.w, depth)
     }
 
-    /// Inspired by https://en.wikipedia.org/wiki/Angular_momentum
-    /// contact: force application point relative to my center of mass
-    fn tug_acc(&self, contact: VecXy, force: VecXy) -> FieldScalars {
-        if force == VecXy::ZERO {
-            // correct: zero force has no effect
-            // necessary: otherwise projection returns NaN
-            return FieldScalars::default();
-        }
-
-        // split force vector up into [force rotatable, force unrotatable]
-        let [fr, fu]: [VecXy; 2] = {
-            // 0. when contact is at center of mass
-            // 1. when contact is at max tug handle distance
-            let rotatable_proportion = contact.length() / self.max_tug_handle_distance;
-            assert!(0. <= rotatable_proportion);
-            assert!(rotatable_proportion <= 1.);
-            let fr = force * rotatable_proportion;
-            [fr, force - fr]
+    /// Real duration simulated by each tick, in seconds
+    const TICK_SECONDS: f32 = 1. / 60.;
+    /// Range of `time_scale`
+    const TIME_SCALE_RANGE: [f32; 2] = [0.05, 4.];
+    /// Default maximum number of ticks simulated per update
+    const MAX_TICKS_PER_UPDATE: usize = 5;
+
+    /// Advance the simulation by one tick within `bounds`
+    fn tick(&mut self, bounds: Rect) {
+        self.world.set_bounds_rect(VecXy::new(bounds.x, bounds.y), VecXy::new(bounds.w, bounds.h));
+        let gravity_well = PointGravity {
+            center: self.drag_target,
+            strength: self.gravity_well_strength.neg_if(self.gravity_well_repels),
+            min_distance: Self::GRAVITY_WELL_MIN_DISTANCE,
+        };
+        let wind = Wind { acc: self.wind, by_cross_section: self.wind_by_cross_section };
+        let pool = self.pool(bounds);
+        let pool = ViscousRegion {
+            min: VecXy::new(pool.left(), pool.top()),
+            max: VecXy::new(pool.right(), pool.bottom()),
+            viscosity: Self::POOL_VISCOSITY,
         };
+        // toggled fields are added for this tick only
+        let mut transient_fields: Vec<&dyn ForceField> = Vec::new();
+        if self.gravity_well_active {
+            transient_fields.push(&gravity_well);
+            // the well follows the cursor, so nothing may rest
+            for body in self.world.bodies.iter_mut() {
+                body.wake();
+            }
+        }
+        if self.wind_enabled {
+            transient_fields.push(&wind);
+        }
+        if self.viscous_pool {
+            transient_fields.push(&pool);
+        }
+        self.world.tick(&transient_fields);
 
-        // split rotatable force up into [parallel, perpindicular] components wrt contact
-        let [fr_parr, fr_perp] = fr.split_parr_perp(contact);
+        let bodies = &self.world.bodies;
+        self.at_rest_count = bodies.iter().filter(|body| body.at_rest()).count();
+        self.auto_pause.update(bodies);
+    }
 
-        FieldScalars {
-            xy: fu + fr.with_length(fr_parr.length()),
-            angle: self.rot_acc_scalar
-                * fr_perp.length()
-                * if contact.angle_between(fr_perp) < 0. { -1. } else { 1. },
+    /// Scene file written and read by the save and load actions
+    const SCENE_PATH: &str = "scene.json";
+    /// Scene file read at startup, left alone by saving
+    const INITIAL_SCENE_PATH: &str = "scene.ron";
+
+    /// Write my gravity and bodies to the scene file at `path`
+    fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        Scene { gravity: self.world.gravity, bodies: self.world.bodies.clone() }.save(path)
+    }
+    /// Replace my gravity and bodies with those in the scene file at `path`
+    fn load(&mut self, ctx: &Context, path: impl AsRef<Path>) -> io::Result<()> {
+        let scene = Scene::load(path)?;
+        self.world.gravity = scene.gravity;
+        self.stashed_gravity = VecXy::ZERO;
+        self.world.set_bodies(scene.bodies);
+        self.world.ticks = 0;
+        self.selected = None;
+        self.follow = None;
+        self.world.history.clear();
+        if self.fit_camera_on_load {
+            self.fit_camera(ctx);
         }
+        Ok(())
+    }
+
+    /// File written and read by the repro actions
+    const REPRO_PATH: &str = "repro.json";
+
+    /// Write the world's config, generator state, tick count and bodies to `path`, so that
+    /// loading it reproduces the world exactly
+    fn save_repro(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.world.repro())?)
+    }
+    /// Resume the world from the repro file at `path`
+    fn load_repro(&mut self, ctx: &Context, path: impl AsRef<Path>) -> io::Result<()> {
+        let repro: Repro = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        self.world.restore(repro);
+        self.stashed_gravity = VecXy::ZERO;
+        self.selected = None;
+        self.follow = None;
+        if self.fit_camera_on_load {
+            self.fit_camera(ctx);
+        }
+        Ok(())
+    }
+
+    /// Fraction of the screen left around the bodies when fitting the camera to them
+    const FIT_MARGIN: f32 = 0.1;
+
+    /// Pan and zoom the camera to frame all bodies
+    fn fit_camera(&mut self, ctx: &Context) {
+        let Some(aabb) = self.world.aabb() else {
+            return;
+        };
+        let view = graphics::screen_coordinates(ctx);
+        let size = VecXy::new(view.w, view.h);
+        self.camera.fit(view.center().into(), size, aabb, Self::FIT_MARGIN);
+        let [min, max] = Self::ZOOM_RANGE;
+        self.camera.zoom = self.camera.zoom.clamp(min, max);
     }
-}
 
-impl MyGame {
     pub fn new(ctx: &mut Context) -> MyGame {
-        MyGame {
-            bodies: [
-                Body {
-                    acc_scalar: 0.2,
-                    rot_acc_scalar: 0.05,
-                    // statics: VelocityStatics {
-                    //     xy: VelocityStatic {
-                    //         acc_scalar: 0.003,
-                    //         linear_friction_scalar: 0.99,
-                    //         constant_friction: 0.001,
-                    //     },
-                    //     angle: VelocityStatic {
-                    //         acc_scalar: 0.00009,
-                    //         linear_friction_scalar: 0.99,
-                    //         constant_friction: 0.0001,
-                    //     },
-                    // },
-                    pos: FieldScalars { xy: VecXy::splat(300.), angle: 1. },
-                    vel: FieldScalars { xy: VecXy::splat(0.), angle: 0. },
-                    scale: VecXy::new(50., 50.),
-                    tuggers: [
-                        None,
-                        Some(Tugger {
-                            world_dest: VecXy::new(300., 280.),
-                            relative_body_handle_xy: VecLa { length: 7., angle: 2. },
-                        }),
-                        // Some(Tugger {
-                        //     world_dest: VecXy::new(400., 220.),
-                        //     relative_body_handle_xy: VecLa { length: 9., angle: 2.4 },
-                        // }),
-                    ],
-                    max_tug_handle_distance: 35.,
-                },
-                Body {
-                    // statics: VelocityStatics {
-                    //     xy: VelocityStatic {
-                    //         acc_scalar: 0.002,
-                    //         linear_friction_scalar: 0.99,
-                    //         constant_friction: 0.001,
-                    //     },
-                    //     angle: VelocityStatic {
-                    //         acc_scalar: 0.00007,
-                    //         linear_friction_scalar: 0.99,
-                    //         constant_friction: 0.0001,
-                    //     },
-                    // },
-                    acc_scalar: 0.2,
-                    rot_acc_scalar: 0.1,
-                    pos: FieldScalars { xy: VecXy::splat(300.), angle: 1. },
-                    vel: FieldScalars { xy: VecXy::splat(0.), angle: 0. },
-                    scale: VecXy::new(80., 30.),
-                    tuggers: [
-                        None,
-                        Some(Tugger {
-                            world_dest: VecXy::new(450., 100.),
-                            relative_body_handle_xy: VecLa { length: 35., angle: 0.3 },
-                        }),
-                        // Some(Tugger {
-                        //     world_dest: VecXy::new(510., 400.),
-                        //     relative_body_handle_xy: VecLa { length: 30., angle: 3.1 },
-                        // }),
-                    ],
-                    max_tug_handle_distance: 80.,
-                },
-            ],
+        let camera = Camera::default();
+        let (scene, loaded) = match Scene::load(Self::INITIAL_SCENE_PATH) {
+            Ok(scene) => (scene, true),
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    eprintln!("failed to load scene, using the default: {}", e);
+                }
+                (Scene::fallback(camera.down()), false)
+            }
+        };
+        let mut world = World::new(scene.bodies, scene.gravity);
+        world.history_len = World::HISTORY_LEN;
+        let mut my_game = MyGame {
+            initial_bodies: world.bodies.clone(),
+            world,
+            at_rest_count: 0,
+            draw_mode: false,
+            drawing: None,
+            stashed_gravity: VecXy::ZERO,
+            camera,
+            fit_camera_on_load: true,
+            follow: None,
+            gravity_well_active: false,
+            gravity_well_repels: false,
+            gravity_well_strength: 500.,
+            wind: VecXy::new(0.03, 0.),
+            wind_enabled: false,
+            wind_by_cross_section: false,
+            viscous_pool: false,
+            key_bindings: default_key_bindings(),
+            bounds_margin: 0.,
+            draw_bounds: false,
+            paused: false,
+            step_once: false,
+            timestep: FixedTimestep::new(Self::TICK_SECONDS, Self::MAX_TICKS_PER_UPDATE),
+            time_scale: 1.,
+            auto_pause: AutoPause::default(),
+            swap_all: false,
+            drag_target: VecXy::ZERO,
+            drag_smoothing: 0.4,
+            mouse_break_force: None,
+            explosion_radius: 150.,
+            explosion_strength: 4.,
+            hovered: None,
+            selected: None,
+            color_tuggers: false,
+            wireframe: false,
+            draw_trails: false,
+            speed_heatmap: false,
+            heatmap_max_speed: 5.,
+            draw_labels: false,
+            debug_draw: false,
+            log_step_timings: false,
+            step_timings: StepTimings::default(),
             rect_mash: Mesh::new_rectangle(
                 ctx,
                 ggez::graphics::DrawMode::fill(),
@@ -251,135 +464,661 @@ impl MyGame {
                 Color::WHITE,
             )
             .expect("new mesh fail"),
+            rect_wire_mash: Mesh::new_rectangle(
+                ctx,
+                ggez::graphics::DrawMode::stroke(0.04),
+                ggez::graphics::Rect { x: -0.5, y: -0.5, w: 1., h: 1. },
+                Color::WHITE,
+            )
+            .expect("new mesh fail"),
+            circle_mesh: Mesh::new_circle(
+                ctx,
+                ggez::graphics::DrawMode::fill(),
+                VecXy::ZERO,
+                0.5,
+                0.005,
+                Color::WHITE,
+            )
+            .expect("new mesh fail"),
+            circle_wire_mesh: Mesh::new_circle(
+                ctx,
+                ggez::graphics::DrawMode::stroke(0.04),
+                VecXy::ZERO,
+                0.5,
+                0.005,
+                Color::WHITE,
+            )
+            .expect("new mesh fail"),
+        };
+        if loaded && my_game.fit_camera_on_load {
+            my_game.fit_camera(ctx);
         }
+        for (index, body) in my_game.world.bodies.iter().enumerate() {
+            for issue in body.validate() {
+                eprintln!("warning: body {}: {}", index, issue);
+            }
+        }
+        my_game
     }
 }
 
+/// Draw a line of the given width from `from` to `to` by stretching the given unit rectangle mesh
+fn draw_segment(
+    ctx: &mut Context,
+    mash: &Mesh,
+    from: VecXy,
+    to: VecXy,
+    width: f32,
+    color: Color,
+) -> GameResult {
+    let la = VecLa::from_xy(from - to);
+    graphics::draw(
+        ctx,
+        mash,
+        DrawParam {
+            trans: Transform::Values {
+                dest: from.into(),
+                rotation: la.angle,
+                scale: VecXy::new(la.length, width).into(),
+                offset: VecXy::new(0.5, 0.).into(),
+            },
+            color,
+            ..Default::default()
+        },
+    )
+}
+
 impl EventHandler for MyGame {
-    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        self.wake();
         if let MouseButton::Left = button {
-            let mouse_xy = VecXy::new(x, y);
-            for body in self.bodies.iter_mut() {
-                let relative_body_handle_xy =
-                    VecLa::from_xy((mouse_xy - body.pos.xy).rotated(-body.pos.angle));
-                if relative_body_handle_xy.length <= body.max_tug_handle_distance {
-                    body.tuggers[0] =
-                        Some(Tugger { relative_body_handle_xy, world_dest: mouse_xy });
-                }
+            let mouse_xy = self.screen_to_world(ctx, VecXy::new(x, y));
+            if self.draw_mode {
+                self.drawing = Some([mouse_xy; 2]);
+                return;
             }
+            if keyboard::is_mod_active(ctx, KeyMods::SHIFT) {
+                // shift clicks choose the body for the camera to follow instead
+                self.follow = self.body_at(mouse_xy);
+                return;
+            }
+            self.drag_target = mouse_xy;
+            // grab only the topmost body, i.e. the last one drawn
+            if let Some(index) = self.world.body_at(mouse_xy) {
+                let body = &mut self.world.bodies[index];
+                body.mouse_tugger = Some(Tugger {
+                    relative_body_handle_xy: body.grab_handle(mouse_xy),
+                    world_dest: mouse_xy,
+                    stiffness: Tugger::DEFAULT_STIFFNESS,
+                    damping: Tugger::DEFAULT_DAMPING,
+                    break_force: self.mouse_break_force,
+                    to_body: None,
+                    work: 0.,
+                });
+                self.selected = Some(body.id);
+            } else {
+                self.follow = None;
+            }
+        }
+        if let MouseButton::Right = button {
+            let mouse_xy = self.screen_to_world(ctx, VecXy::new(x, y));
+            self.explode_at(mouse_xy);
         }
     }
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
         if let MouseButton::Left = button {
-            for body in self.bodies.iter_mut() {
-                body.tuggers[0] = None;
+            if let Some([start, end]) = self.drawing.take() {
+                self.world.add_body(Body::new_spanning(start, end));
+            }
+            for body in self.world.bodies.iter_mut() {
+                body.mouse_tugger = None;
             }
         }
     }
-    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
-        for body in self.bodies.iter_mut() {
-            if let Some(tugger) = &mut body.tuggers[0] {
-                tugger.world_dest = VecXy::new(x, y);
-            }
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        // middle drags move the view along with the cursor
+        if mouse::button_pressed(ctx, MouseButton::Middle) {
+            self.camera.pan(-VecXy::new(dx, dy));
+        }
+        let mouse_xy = self.screen_to_world(ctx, VecXy::new(x, y));
+        if let Some([_, end]) = &mut self.drawing {
+            *end = mouse_xy;
+        }
+        self.drag_target = mouse_xy;
+        // topmost, like grabbing
+        self.hovered = self.body_at(mouse_xy);
+    }
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        let factor = Self::WHEEL_SCALE_FACTOR.powf(y);
+        if let Some(body) = self.world.bodies.iter_mut().find(|body| body.mouse_tugger.is_some()) {
+            body.rescale(factor);
+        } else {
+            self.zoom_at(ctx, mouse::position(ctx).into(), factor);
         }
     }
     fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _: KeyMods, repeat: bool) {
         if repeat {
             return;
         }
-        match keycode {
-            KeyCode::Escape => quit(ctx),
-            KeyCode::Space => {
-                for body in self.bodies[1..].iter_mut() {
-                    for tugger in body.tuggers.iter_mut().filter_map(Option::as_mut) {
-                        let [x, y]: [f32; 2] = tugger.world_dest.into();
-                        tugger.world_dest = VecXy::new(y, x);
+        let action = match self.key_bindings.action(keycode) {
+            Some(action) => action,
+            None => return,
+        };
+        self.wake();
+        match action {
+            Action::Quit => {
+                // the first press stops following, if any
+                if self.follow.take().is_none() {
+                    quit(ctx);
+                }
+            }
+            Action::ToggleDrawMode => {
+                self.draw_mode = !self.draw_mode;
+                self.drawing = None;
+            }
+            Action::CycleBoundaryMode => self.world.boundary_mode = self.world.boundary_mode.next(),
+            Action::Shake => self.world.shake(),
+            Action::ToggleSwapAll => self.swap_all = !self.swap_all,
+            Action::ToggleIgnoreGravity => {
+                let mouse_xy = self.screen_to_world(ctx, mouse::position(ctx).into());
+                if let Some((index, _)) = self.world.nearest_body(mouse_xy) {
+                    let body = &mut self.world.bodies[index];
+                    body.ignore_gravity = !body.ignore_gravity;
+                }
+            }
+            Action::RotateCcw => {
+                if let Some(body) = self.selected_body_mut() {
+                    body.rotate_by(-Body::ROTATE_STEP);
+                }
+            }
+            Action::RotateCw => {
+                if let Some(body) = self.selected_body_mut() {
+                    body.rotate_by(Body::ROTATE_STEP);
+                }
+            }
+            Action::ToggleJitter => self.world.jitter = !self.world.jitter,
+            Action::ToggleRopeColors => self.color_tuggers = !self.color_tuggers,
+            Action::ToggleWireframe => self.wireframe = !self.wireframe,
+            Action::ToggleDebugDraw => self.debug_draw = !self.debug_draw,
+            Action::TogglePause => self.paused = !self.paused,
+            Action::Rewind => {
+                if self.paused {
+                    self.world.rewind()
+                }
+            }
+            Action::SwapDests => self.world.swap_dests(self.swap_all),
+            Action::SnapToFloor => {
+                let bounds = self.bounds(ctx);
+                let [floor, down] =
+                    if self.camera.y_up { [bounds.top(), -1.] } else { [bounds.bottom(), 1.] };
+                if let Some(body) = self.selected_body_mut() {
+                    body.snap_to_floor(floor, down);
+                }
+            }
+            Action::ToggleStepTimings => {
+                self.log_step_timings = !self.log_step_timings;
+                self.step_timings = StepTimings::default();
+            }
+            Action::MirrorScene => {
+                let center_x = self.bounds(ctx).center().x;
+                for body in self.world.bodies.iter_mut() {
+                    body.mirror_x(center_x);
+                }
+            }
+            Action::ToggleAdaptiveTimestep => {
+                self.world.adaptive_timestep = !self.world.adaptive_timestep
+            }
+            Action::ToggleAutoPause => self.auto_pause.enabled = !self.auto_pause.enabled,
+            Action::RandomizeConstants => {
+                for body in self.world.bodies.iter_mut() {
+                    body.perturb_constants(&mut self.world.rng, Self::CONSTANTS_SPREAD);
+                }
+            }
+            Action::ToggleLabels => self.draw_labels = !self.draw_labels,
+            Action::ToggleBounds => self.draw_bounds = !self.draw_bounds,
+            Action::ToggleGravity => {
+                std::mem::swap(&mut self.world.gravity, &mut self.stashed_gravity)
+            }
+            Action::SaveScene => {
+                if let Err(e) = self.save(Self::SCENE_PATH) {
+                    eprintln!("failed to save scene: {}", e);
+                }
+            }
+            Action::LoadScene => {
+                self.wake();
+                if let Err(e) = self.load(ctx, Self::SCENE_PATH) {
+                    eprintln!("failed to load scene: {}", e);
+                }
+            }
+            Action::Reset => {
+                self.wake();
+                self.reset();
+            }
+            Action::SpawnBody => {
+                let mouse_xy = self.screen_to_world(ctx, mouse::position(ctx).into());
+                let half = VecXy::splat(Self::SPAWN_SIZE * 0.5);
+                self.world.add_body(Body::new_spanning(mouse_xy - half, mouse_xy + half));
+            }
+            Action::DeleteBody => {
+                let mouse_xy = self.screen_to_world(ctx, mouse::position(ctx).into());
+                self.delete_body_at(mouse_xy);
+            }
+            Action::ToggleSpeedHeatmap => self.speed_heatmap = !self.speed_heatmap,
+            Action::SlowDown => self.scale_time(0.5),
+            Action::SpeedUp => self.scale_time(2.),
+            Action::StepOnce => self.step_once = self.paused,
+            Action::ToggleTrails => {
+                self.draw_trails = !self.draw_trails;
+                self.world.trail_length = if self.draw_trails { Self::TRAIL_LENGTH } else { 0 };
+                for body in self.world.bodies.iter_mut() {
+                    body.trail.clear();
+                }
+            }
+            Action::ToggleWind => self.wind_enabled = !self.wind_enabled,
+            Action::ToggleStatic => {
+                let mouse_xy = self.screen_to_world(ctx, mouse::position(ctx).into());
+                if let Some((index, _)) = self.world.nearest_body(mouse_xy) {
+                    let body = &mut self.world.bodies[index];
+                    body.static_body = !body.static_body;
+                    body.vel = FieldScalars::default();
+                }
+            }
+            Action::ResetCamera => {
+                self.camera = Camera { y_up: self.camera.y_up, ..Camera::default() }
+            }
+            Action::CycleIntegrator => self.world.integrator = self.world.integrator.next(),
+            Action::ToggleViscousPool => self.viscous_pool = !self.viscous_pool,
+            Action::FlipYAxis => {
+                self.camera.y_up = !self.camera.y_up;
+                // gravity keeps pointing down on screen
+                self.world.gravity.y = -self.world.gravity.y;
+                self.stashed_gravity.y = -self.stashed_gravity.y;
+            }
+            Action::PinTugger => {
+                // dragged bodies stay tugged toward where the cursor is
+                for body in self.world.bodies.iter_mut() {
+                    if let Some(tugger) = body.mouse_tugger.take() {
+                        body.add_tugger(tugger);
                     }
                 }
             }
-            _ => {}
+            Action::ToggleShape => {
+                if let Some(body) = self.selected_body_mut() {
+                    body.toggle_shape();
+                }
+            }
+            Action::SaveRepro => {
+                if let Err(e) = self.save_repro(Self::REPRO_PATH) {
+                    eprintln!("failed to save repro: {}", e);
+                }
+            }
+            Action::LoadRepro => {
+                self.wake();
+                if let Err(e) = self.load_repro(ctx, Self::REPRO_PATH) {
+                    eprintln!("failed to load repro: {}", e);
+                }
+            }
         }
     }
-    fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
-        for body in self.bodies.iter_mut() {
-            // update velocity wrt tug
-            let mut acc = body
-                .tuggers
-                .iter()
-                .filter_map(Option::as_ref)
-                .map(|tugger| {
-                    let xy_relative_handle =
-                        body.xy_relative_handle(tugger.relative_body_handle_xy);
-
-                    let min_ticks_to_stop = body.vel.xy.length() / body.acc_scalar;
-                    let rel_stop_at = body.vel.xy * min_ticks_to_stop * 0.5;
-                    let rel_target = tugger.world_dest - (body.pos.xy + xy_relative_handle);
-                    let force = (rel_target - rel_stop_at).length_capped(body.acc_scalar);
-
-                    // let force = tugger.world_dest - (xy_relative_handle + body.pos.xy);
-                    body.tug_acc(xy_relative_handle, force)
-                })
-                .fold(FieldScalars::default(), FieldScalars::add);
-
-            //gravity
-            acc.xy.y += 0.1;
-
-            body.vel.add_from(&acc);
-            // accelerate
-            body.pos.add_from(&body.vel);
-
-            // // linear friction
-            // body.vel.xy *= body.statics.xy.linear_friction_scalar;
-            // body.vel.angle *= body.statics.angle.linear_friction_scalar;
-
-            // // constant friction
-            // body.vel.xy = body.vel.xy.reduce_length_saturating(body.statics.xy.constant_friction);
-            // body.vel.angle =
-            //     body.vel.angle.toward_zero_saturating(body.statics.angle.constant_friction);
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // mouse tuggers follow the cursor even while paused
+        for body in self.world.bodies.iter_mut() {
+            if let Some(tugger) = &mut body.mouse_tugger {
+                tugger.smooth_dest_toward(self.drag_target, self.drag_smoothing);
+            }
+        }
+
+        // arrow keys pan the camera, even while paused
+        let pan = [
+            (KeyCode::Left, VecXy::new(-1., 0.)),
+            (KeyCode::Right, VecXy::new(1., 0.)),
+            (KeyCode::Up, VecXy::new(0., -1.)),
+            (KeyCode::Down, VecXy::new(0., 1.)),
+        ]
+        .into_iter()
+        .filter(|&(key, _)| keyboard::is_key_pressed(ctx, key))
+        .map(|(_, direction)| direction)
+        .sum::<VecXy>();
+        self.camera.pan(pan * Self::PAN_SPEED * timer::delta(ctx).as_secs_f32());
+
+        // the camera eases toward centering the followed body
+        if let Some(body) = self.follow.and_then(|handle| self.world.get(handle)) {
+            let screen_center = graphics::screen_coordinates(ctx).center().into();
+            self.camera.follow(body.pos.xy, screen_center, Self::FOLLOW_SMOOTHING);
+        }
+
+        // ctrl attracts bodies to the cursor, alt repels them
+        let [ctrl, alt] =
+            [KeyMods::CTRL, KeyMods::ALT].map(|mods| keyboard::is_mod_active(ctx, mods));
+        self.gravity_well_active = ctrl || alt;
+        self.gravity_well_repels = alt;
+
+        if self.paused || self.auto_pause.paused {
+            // time spent paused isn't caught up on afterward
+            self.timestep.accumulator = 0.;
+            if self.step_once {
+                self.step_once = false;
+                let bounds = self.bounds(ctx);
+                self.tick(bounds);
+            }
+            return Ok(());
+        }
+        let bounds = self.bounds(ctx);
+        let ticks = self.timestep.ticks_due(timer::delta(ctx).as_secs_f32() * self.time_scale);
+        let mut ticking = None;
+        for _ in 0..ticks {
+            if self.auto_pause.paused {
+                break;
+            }
+            let started = Instant::now();
+            self.tick(bounds);
+            *ticking.get_or_insert(Duration::ZERO) += started.elapsed();
+        }
+
+        if self.log_step_timings {
+            // frames without ticks aren't sampled
+            let stats = ticking.and_then(|ticking| self.step_timings.record(ticking));
+            if let Some([min, mean, max]) = stats {
+                eprintln!("tick timings: min {:?} mean {:?} max {:?}", min, mean, max);
+            }
         }
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
         graphics::clear(ctx, Color::BLACK);
+        let view = graphics::screen_coordinates(ctx);
+        let bounds = self.bounds(ctx);
+        graphics::set_screen_coordinates(ctx, self.world_view(view))?;
+        // lines are a single pixel wide whatever the zoom
+        let line_width = self.camera.world_length(1.);
+
+        // hint where bodies can be grabbed while the grabbing button is held
+        let grab_hint = !self.draw_mode && mouse::button_pressed(ctx, MouseButton::Left);
+        for body in self.world.bodies.iter_mut() {
+            // draw trail, fading toward its oldest point
+            for (age, &xy) in body.trail.iter().rev().enumerate() {
+                let alpha = 0.5 * (1. - age as f32 / body.trail.len() as f32);
+                graphics::draw(
+                    ctx,
+                    &self.rect_mash,
+                    DrawParam {
+                        trans: Transform::Values {
+                            dest: xy.into(),
+                            rotation: 0.,
+                            scale: VecXy::splat(Self::TRAIL_DOT_SIZE).into(),
+                            offset: VecXy::ZERO.into(),
+                        },
+                        color: Color::new(1., 1., 1., alpha),
+                        ..Default::default()
+                    },
+                )?;
+            }
 
-        for body in self.bodies.iter_mut() {
             // draw body
+            body.update_sleep_fade();
+            let (mesh, scale) = body.mesh(self.wireframe);
+            let body_mash = match mesh {
+                MeshKind::Rect => &self.rect_mash,
+                MeshKind::RectWire => &self.rect_wire_mash,
+                MeshKind::Circle => &self.circle_mesh,
+                MeshKind::CircleWire => &self.circle_wire_mesh,
+            };
             graphics::draw(
                 ctx,
-                &self.rect_mash,
+                body_mash,
                 DrawParam {
                     trans: Transform::Values {
                         dest: body.pos.xy.into(),
                         rotation: body.pos.angle,
-                        scale: body.scale.into(),
+                        scale: scale.into(),
                         offset: VecXy::ZERO.into(),
                     },
-                    color: Color::WHITE,
+                    color: if self.hovered == Some(body.id) {
+                        Self::HOVER_COLOR
+                    } else if self.speed_heatmap {
+                        // angular speed is weighted by the body's half diagonal
+                        let speed = body.vel.xy.length()
+                            + body.vel.angle.abs() * (body.scale * 0.5).length();
+                        let heat = safe_div(speed, self.heatmap_max_speed, 1.).min(1.);
+                        Color::new(heat, 0., 1. - heat, 1.)
+                    } else {
+                        // sleeping bodies are dimmed
+                        let dim = 1. - Self::SLEEP_DIMMING * body.sleep_fade;
+                        let [r, g, b, a] = body.color;
+                        Color::new(r * dim, g * dim, b * dim, a)
+                    },
                     ..Default::default()
                 },
             )?;
 
+            // draw grab reach
+            if grab_hint {
+                let reach = Mesh::new_circle(
+                    ctx,
+                    ggez::graphics::DrawMode::stroke(line_width),
+                    body.pos.xy,
+                    body.max_tug_handle_distance,
+                    line_width * 0.25,
+                    Color::new(1., 1., 1., 0.3),
+                )?;
+                graphics::draw(ctx, &reach, DrawParam::default())?;
+            }
+
+            // draw net linear acceleration
+            if self.debug_draw {
+                let to = body.pos.xy + body.acc.xy * Self::ACC_DRAW_SCALE;
+                draw_segment(ctx, &self.rect_mash, body.pos.xy, to, line_width, Color::GREEN)?;
+            }
+
+            // draw linear velocity
+            if self.debug_draw {
+                let arrow =
+                    (body.vel.xy * Self::VEL_DRAW_SCALE).length_capped(Self::MAX_VEL_DRAW_LENGTH);
+                draw_segment(
+                    ctx,
+                    &self.rect_mash,
+                    body.pos.xy,
+                    body.pos.xy + arrow,
+                    line_width,
+                    Color::BLUE,
+                )?;
+            }
+
+            // draw angular velocity as a tick offset from my facing, colored by sign
+            if self.debug_draw {
+                let tick = VecLa {
+                    length: body.scale.min_element() * 0.5,
+                    angle: body.pos.angle + body.vel.angle * Self::SPIN_DRAW_SCALE,
+                };
+                let color = if body.vel.angle < 0. { Color::RED } else { Color::GREEN };
+                draw_segment(
+                    ctx,
+                    &self.rect_mash,
+                    body.pos.xy,
+                    body.pos.xy + tick.to_xy(),
+                    line_width,
+                    color,
+                )?;
+            }
+
             // draw tug ropes
-            for tugger in body.tuggers.iter().filter_map(Option::as_ref) {
+            for (index, tugger) in body.all_tuggers().enumerate() {
                 let body_handle_xy = body.absolute_handle(tugger.relative_body_handle_xy);
-                let rope_la = VecLa::from_xy(body_handle_xy - tugger.world_dest);
-                graphics::draw(
+                let color = if self.color_tuggers {
+                    Tugger::palette_color(index).into()
+                } else {
+                    Color::RED
+                };
+                draw_segment(
                     ctx,
                     &self.rect_mash,
-                    DrawParam {
-                        trans: Transform::Values {
-                            dest: body_handle_xy.into(),
-                            rotation: rope_la.angle,
-                            scale: VecXy::new(rope_la.length, 1.).into(),
-                            offset: VecXy::new(0.5, 0.).into(),
-                        },
-                        color: Color::RED,
-                        ..Default::default()
-                    },
+                    body_handle_xy,
+                    tugger.world_dest,
+                    line_width,
+                    color,
+                )?;
+
+                // draw handle angle arc
+                if self.debug_draw {
+                    let radius = tugger.relative_body_handle_xy.length;
+                    let [start, end] = body.handle_arc_angles(tugger.relative_body_handle_xy);
+                    let steps = ((end - start).abs() / Self::ARC_STEP_ANGLE).ceil() as usize;
+                    if radius > 0. && steps > 0 {
+                        let points: Vec<VecXy> = (0..=steps)
+                            .map(|i| {
+                                let angle = start + (end - start) * i as f32 / steps as f32;
+                                body.pos.xy + VecLa { length: radius, angle }.to_xy()
+                            })
+                            .collect();
+                        let arc = Mesh::new_line(ctx, &points, line_width, Color::YELLOW)?;
+                        graphics::draw(ctx, &arc, DrawParam::default())?;
+                    }
+                }
+            }
+        }
+
+        // draw contact normals of the latest collisions
+        if self.debug_draw {
+            for event in &self.world.events {
+                let PhysicsEvent::Collision { normal, point, .. } = *event;
+                let to = point + normal * Self::NORMAL_DRAW_LENGTH;
+                draw_segment(
+                    ctx,
+                    &self.rect_mash,
+                    point,
+                    to,
+                    line_width,
+                    Color::new(1., 0.5, 0., 1.),
                 )?;
             }
         }
+
+        // draw separations of overlapping bodies
+        if self.debug_draw {
+            for (index, a) in self.world.bodies.iter().enumerate() {
+                for b in &self.world.bodies[index + 1..] {
+                    if let Some(collision) = a.collides_with(b) {
+                        let to = b.pos.xy + collision.mtv;
+                        draw_segment(
+                            ctx,
+                            &self.rect_mash,
+                            b.pos.xy,
+                            to,
+                            line_width,
+                            Color::MAGENTA,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        // draw viscous pool
+        if self.viscous_pool {
+            let pool = Mesh::new_rectangle(
+                ctx,
+                ggez::graphics::DrawMode::fill(),
+                self.pool(bounds),
+                Color::new(0.2, 0.4, 1., 0.25),
+            )?;
+            graphics::draw(ctx, &pool, DrawParam::default())?;
+        }
+
+        // draw the bounds the world last ticked within
+        if let Some([min, size]) = self.world.bounds_rect().filter(|_| self.draw_bounds) {
+            let outline = Mesh::new_rectangle(
+                ctx,
+                ggez::graphics::DrawMode::stroke(line_width),
+                Rect::new(min.x, min.y, size.x, size.y),
+                Color::CYAN,
+            )?;
+            graphics::draw(ctx, &outline, DrawParam::default())?;
+        }
+
+        // draw preview of the body being drawn
+        if let Some([start, end]) = self.drawing {
+            graphics::draw(
+                ctx,
+                &self.rect_mash,
+                DrawParam {
+                    trans: Transform::Values {
+                        dest: ((start + end) * 0.5).into(),
+                        rotation: 0.,
+                        scale: (end - start).abs().into(),
+                        offset: VecXy::ZERO.into(),
+                    },
+                    color: Color::new(1., 1., 1., 0.4),
+                    ..Default::default()
+                },
+            )?;
+        }
+
+        // draw diagnostic readout in unflipped screen coordinates
+        graphics::set_screen_coordinates(ctx, view)?;
+        let momentum = self.world.total_linear_momentum();
+        let mut readout = format!(
+            "at rest: {} ({} asleep) / active: {}\nmomentum: ({:.2}, {:.2})\nkinetic energy: {:.2}",
+            self.at_rest_count,
+            self.world.bodies.iter().filter(|body| body.asleep).count(),
+            self.world.bodies.len() - self.at_rest_count,
+            momentum.x,
+            momentum.y,
+            self.world.total_kinetic_energy(),
+        );
+        if self.camera.zoom != 1. {
+            readout += &format!("\nzoom: {:.2}", self.camera.zoom);
+        }
+        if self.time_scale != 1. {
+            readout += &format!("\ntime scale: {:.2}", self.time_scale);
+        }
+        if self.world.integrator != Integrator::default() {
+            readout += &format!("\nintegrator: {:?}", self.world.integrator);
+        }
+        if self.world.boundary_mode != BoundaryMode::default() {
+            readout += &format!("\nboundary: {:?}", self.world.boundary_mode);
+        }
+        if self.paused {
+            readout += "\nPAUSED";
+        } else if self.auto_pause.paused {
+            readout += "\nauto-paused at rest";
+        }
+        if self.debug_draw {
+            let mouse_xy = self.screen_to_world(ctx, mouse::position(ctx).into());
+            if let Some((index, distance)) = self.world.nearest_body(mouse_xy) {
+                readout += &format!("\nnearest body: {} ({:.1} away)", index, distance);
+            }
+            if let Some(body) = self.selected.and_then(|handle| self.world.get(handle)) {
+                for (index, tugger) in body.all_tuggers().enumerate() {
+                    readout += &format!("\ntugger {} work: {:.2}", index, tugger.work);
+                }
+            }
+        }
+        let readout = Text::new(readout);
+        graphics::draw(ctx, &readout, DrawParam::default())?;
+
+        // draw wind direction indicator in the top right corner
+        if self.wind_enabled {
+            let origin = VecXy::new(view.right() - Self::WIND_INDICATOR_LENGTH, view.top())
+                + VecXy::splat(Self::WIND_INDICATOR_LENGTH * 0.5);
+            let direction = self.wind.normalize_or_zero() * VecXy::new(1., self.camera.down());
+            let arrow = direction * Self::WIND_INDICATOR_LENGTH * 0.5;
+            draw_segment(ctx, &self.rect_mash, origin - arrow, origin + arrow, 1., Color::CYAN)?;
+            let head = [0.5, -0.5].map(|turn| origin + arrow - arrow.rotated(turn) * 0.4);
+            for tip in head {
+                draw_segment(ctx, &self.rect_mash, origin + arrow, tip, 1., Color::CYAN)?;
+            }
+        }
+
+        // draw body index labels
+        if self.draw_labels {
+            for (center, text) in self.world.labels() {
+                let label = Text::new(text);
+                let half_size = VecXy::new(label.width(ctx), label.height(ctx)) * 0.5;
+                let dest = self.world_to_screen(ctx, center) - half_size;
+                graphics::draw(ctx, &label, DrawParam::default().dest(dest))?;
+            }
+        }
+
         graphics::present(ctx)
     }
 }